@@ -0,0 +1,191 @@
+
+//! #  Escolha do algoritmo da árvore geradora mínima
+//! Permite selecionar pelo nome qual implementação gera a AGM e conferir se elas concordam entre si.
+
+#![allow(warnings)]
+
+use crate::structs::{Graph};
+use crate::kruskal::kruskal;
+use crate::prim::prim;
+use crate::boruvka::boruvka;
+
+/// Nomes aceitos por `gerar_agm`
+pub const ALGORITMOS_AGM: [&str; 3] = ["kruskal", "prim", "boruvka"];
+
+/// Gera a Árvore Geradora Minima com o algoritmo escolhido pelo nome ("kruskal", "prim" ou "boruvka").
+/// O Borůvka usa todas as threads disponíveis na máquina.
+pub fn gerar_agm(algoritmo: &str, gr: &Graph) -> Result<Graph, String> {
+    match algoritmo.trim().to_lowercase().as_str() {
+        "kruskal" => kruskal(gr),
+        "prim" => prim(gr),
+        "boruvka" => {
+            let num_threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+            boruvka(gr, num_threads)
+        }
+        outro => Err(format!("Algoritmo de AGM desconhecido: '{}'. Opções: {:?}", outro, ALGORITMOS_AGM)),
+    }
+}
+
+/// Retorna a soma dos pesos de todas as arestas (não direcionadas) do grafo
+pub fn peso_total(gr: &Graph) -> f64 {
+    gr.get_undirected_edges().iter().fold(0.0, |soma, e| soma + e.weight.valor())
+}
+
+/// Confere se `arvore` é uma árvore geradora de `gr`: mesmos vértices, n - 1 arestas e todos alcançáveis
+fn eh_arvore_geradora(gr: &Graph, arvore: &Graph) -> bool {
+    let n = gr.vertices.len();
+    if arvore.vertices.len() != n {
+        return false;
+    }
+    if n == 0 {
+        return true;
+    }
+    if arvore.get_undirected_edges().len() != n - 1 {
+        return false;
+    }
+
+    // Busca em largura a partir do vértice 0 contando os alcançados
+    let mut visitado = vec![false; n];
+    let mut fila = std::collections::VecDeque::from([0]);
+    visitado[0] = true;
+    let mut alcancados = 1;
+    while let Some(v) = fila.pop_front() {
+        for edge in &arvore.adj[v] {
+            if !visitado[edge.to_idx] {
+                visitado[edge.to_idx] = true;
+                alcancados += 1;
+                fila.push_back(edge.to_idx);
+            }
+        }
+    }
+    alcancados == n
+}
+
+/// Roda todos os algoritmos de AGM no grafo e confere se cada um gera uma árvore geradora
+/// e se todas têm o mesmo peso total. Retorna o peso total em caso de sucesso.
+pub fn checar_consistencia_agm(gr: &Graph) -> Result<f64, String> {
+    let mut referencia: Option<(&str, f64)> = None;
+
+    for algoritmo in ALGORITMOS_AGM {
+        let arvore = gerar_agm(algoritmo, gr)?;
+        if !eh_arvore_geradora(gr, &arvore) {
+            return Err(format!("O resultado de '{}' não é uma árvore geradora.", algoritmo));
+        }
+
+        let peso = peso_total(&arvore);
+        match referencia {
+            None => referencia = Some((algoritmo, peso)),
+            Some((nome_ref, peso_ref)) => {
+                // Tolerância relativa pois a soma de f64 depende da ordem das arestas
+                let tolerancia = 1e-9 * peso_ref.abs().max(1.0);
+                if (peso - peso_ref).abs() > tolerancia {
+                    return Err(format!(
+                        "Pesos diferentes: '{}' = {:.4}, '{}' = {:.4}",
+                        nome_ref, peso_ref, algoritmo, peso
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(referencia.map(|(_, peso)| peso).unwrap_or(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boruvka::boruvka;
+    use crate::structs::Point;
+
+    /// Arestas da árvore como pares (menor índice, maior índice), ordenadas
+    fn pares(arvore: &Graph) -> Vec<(usize, usize)> {
+        let mut pares: Vec<_> = arvore.get_undirected_edges().iter().map(|e| (e.from.min(e.to), e.from.max(e.to))).collect();
+        pares.sort();
+        pares
+    }
+
+    /// Casa com telhado: quadrado 0-1-2-3 com diagonais e o vértice 4 em cima.
+    /// A AGM é 0-1 (1), 1-2 (2), 2-3 (1) e 2-4 (1.5), com peso total 5.5.
+    fn casa() -> Graph {
+        let mut gr = Graph::new();
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (1.0, 2.0), (0.0, 2.0), (0.5, 3.0)] {
+            gr.add_vertex(Point { x, y });
+        }
+        for (u, v, w) in [(0, 1, 1.0), (1, 2, 2.0), (2, 3, 1.0), (3, 0, 2.5), (0, 2, 3.0), (1, 3, 3.0), (2, 4, 1.5), (3, 4, 1.6)] {
+            gr.add_edge(u, v, w).unwrap();
+        }
+        gr
+    }
+
+    #[test]
+    fn agm_da_casa() {
+        let gr = casa();
+        for algoritmo in ALGORITMOS_AGM {
+            let arvore = gerar_agm(algoritmo, &gr).unwrap();
+            assert!(eh_arvore_geradora(&gr, &arvore), "{} não gerou uma árvore geradora", algoritmo);
+            assert!((peso_total(&arvore) - 5.5).abs() < 1e-12, "{}: peso {}", algoritmo, peso_total(&arvore));
+        }
+        assert!((checar_consistencia_agm(&gr).unwrap() - 5.5).abs() < 1e-12);
+
+        // O nome é aceito sem diferenciar maiúsculas e com espaços em volta
+        assert!(gerar_agm(" Prim ", &gr).is_ok());
+        assert!(gerar_agm("dijkstra", &gr).is_err());
+    }
+
+    #[test]
+    fn eh_arvore_geradora_recusa_ciclo_e_floresta() {
+        let gr = casa();
+        let mut com_ciclo = Graph::new();
+        let mut floresta = Graph::new();
+        for p in &gr.vertices {
+            com_ciclo.add_vertex(*p);
+            floresta.add_vertex(*p);
+        }
+        // 4 arestas, mas 0-1-2 fecha um ciclo e o vértice 3 fica de fora
+        for (u, v) in [(0, 1), (1, 2), (2, 0), (2, 4)] {
+            com_ciclo.add_edge(u, v, 1.0).unwrap();
+        }
+        for (u, v) in [(0, 1), (2, 3), (2, 4)] {
+            floresta.add_edge(u, v, 1.0).unwrap();
+        }
+        assert!(!eh_arvore_geradora(&gr, &com_ciclo));
+        assert!(!eh_arvore_geradora(&gr, &floresta));
+        assert!(eh_arvore_geradora(&Graph::new(), &Graph::new()));
+    }
+
+    #[test]
+    fn boruvka_concorda_com_pesos_empatados() {
+        // Grade 6x6 com todas as arestas de peso 1: o desempate pelos índices decide a árvore
+        let mut gr = Graph::new();
+        for y in 0..6 {
+            for x in 0..6 {
+                gr.add_vertex(Point { x: x as f64, y: y as f64 });
+            }
+        }
+        for y in 0..6 {
+            for x in 0..6 {
+                let i = y * 6 + x;
+                if x < 5 {
+                    gr.add_edge(i, i + 1, 1.0).unwrap();
+                }
+                if y < 5 {
+                    gr.add_edge(i, i + 6, 1.0).unwrap();
+                }
+            }
+        }
+
+        let referencia = pares(&gerar_agm("kruskal", &gr).unwrap());
+        assert_eq!(referencia.len(), 35);
+        assert_eq!(pares(&gerar_agm("prim", &gr).unwrap()), referencia);
+        for num_threads in [1, 2, 3, 8] {
+            assert_eq!(pares(&boruvka(&gr, num_threads).unwrap()), referencia, "{} threads", num_threads);
+        }
+        assert_eq!(checar_consistencia_agm(&gr).unwrap(), 35.0);
+    }
+
+    #[test]
+    fn boruvka_da_casa() {
+        let gr = casa();
+        assert_eq!(pares(&gerar_agm("boruvka", &gr).unwrap()), vec![(0, 1), (1, 2), (2, 3), (2, 4)]);
+    }
+}
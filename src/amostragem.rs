@@ -0,0 +1,621 @@
+
+//! #  Estratégias de amostragem para o PRM
+//! A amostragem uniforme raramente coloca vértices dentro de portas e corredores estreitos, o que deixa o grafo desconexo.
+//! Aqui cada estratégia implementa o trait `Sampler` e o `AmostradorMisto` combina várias por proporção.
+//! ## Estratégias
+//! Uniforme;\
+//! Gaussiana (pontos livres perto de obstáculos);\
+//! Teste da ponte (pontos livres entre dois obstáculos, ou seja, em passagens estreitas);\
+//! Baseada em obstáculo (parte de dentro do obstáculo e anda até sair dele);\
+//! Eixo medial (empurra o ponto para longe do obstáculo mais próximo até ficar no meio do espaço livre);\
+//! Quasi-aleatórias de baixa discrepância (Halton, Sobol e grade com jitter), que cobrem o mapa de forma mais
+//! uniforme que a amostragem aleatória. Halton e Sobol são determinísticas: a mesma sequência em toda execução.
+
+#![allow(warnings)]
+
+use crate::readmap::OccupancyMap;
+use crate::structs::Point;
+use rand::{Rng, RngCore};
+
+/// Uma estratégia de amostragem de vértices do PRM
+pub trait Sampler {
+    /// Tenta gerar um ponto livre. Retorna `None` se esta tentativa não produziu um ponto
+    /// (quem chama tenta de novo).
+    fn sample(&mut self, map: &OccupancyMap, rng: &mut dyn RngCore) -> Option<Point>;
+}
+
+/// Converte um ponto do quadrado unitário [0, 1)² para os limites de navegação do mapa
+fn escalar_para_limites(map: &OccupancyMap, u: f64, v: f64) -> Point {
+    let (x_min, y_min, x_max, y_max) = map.pixel_bounds;
+    Point {
+        x: x_min as f64 + u * (x_max - x_min) as f64,
+        y: y_min as f64 + v * (y_max - y_min) as f64,
+    }
+}
+
+/// Ponto uniforme dentro dos limites de navegação (pode estar obstruído)
+fn ponto_nos_limites(map: &OccupancyMap, rng: &mut dyn RngCore) -> Point {
+    let (x_min, y_min, x_max, y_max) = map.pixel_bounds;
+    Point {
+        x: rng.gen_range(x_min as f64..=x_max as f64),
+        y: rng.gen_range(y_min as f64..=y_max as f64),
+    }
+}
+
+/// Retorna true se o ponto está dentro dos limites de navegação do mapa
+fn dentro_dos_limites(map: &OccupancyMap, p: &Point) -> bool {
+    let (x_min, y_min, x_max, y_max) = map.pixel_bounds;
+    p.x >= x_min as f64 && p.x <= x_max as f64 && p.y >= y_min as f64 && p.y <= y_max as f64
+}
+
+/// Ponto a uma distância normal (desvio `sigma`) de `centro`, em direção aleatória (Box-Muller)
+fn vizinho_gaussiano(centro: &Point, sigma: f64, rng: &mut dyn RngCore) -> Point {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen_range(0.0..1.0);
+    let raio = sigma * (-2.0 * u1.ln()).sqrt();
+    let angulo = std::f64::consts::TAU * u2;
+    Point { x: centro.x + raio * angulo.cos(), y: centro.y + raio * angulo.sin() }
+}
+
+/// Amostragem uniforme nos limites de navegação (mesma distribuição do `sample_valid_point`)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AmostradorUniforme;
+
+impl Sampler for AmostradorUniforme {
+    fn sample(&mut self, map: &OccupancyMap, rng: &mut dyn RngCore) -> Option<Point> {
+        let p = ponto_nos_limites(map, rng);
+        if map.is_obstructed(&p) { None } else { Some(p) }
+    }
+}
+
+/// Amostragem gaussiana: sorteia um par de pontos a uma distância ~N(0, sigma);
+/// se só um deles estiver livre, ele é retornado (fica perto da borda de um obstáculo)
+#[derive(Debug, Clone, Copy)]
+pub struct AmostradorGaussiano {
+    /// Desvio padrão da distância entre os dois pontos (em pixels)
+    pub sigma: f64,
+}
+
+impl Sampler for AmostradorGaussiano {
+    fn sample(&mut self, map: &OccupancyMap, rng: &mut dyn RngCore) -> Option<Point> {
+        let p1 = ponto_nos_limites(map, rng);
+        let p2 = vizinho_gaussiano(&p1, self.sigma, rng);
+        let livre1 = !map.is_obstructed(&p1);
+        let livre2 = dentro_dos_limites(map, &p2) && !map.is_obstructed(&p2);
+
+        match (livre1, livre2) {
+            (true, false) => Some(p1),
+            (false, true) => Some(p2),
+            _ => None,
+        }
+    }
+}
+
+/// Teste da ponte: sorteia dois pontos obstruídos a uma distância ~N(0, sigma);
+/// se o ponto médio estiver livre, ele está numa passagem estreita
+#[derive(Debug, Clone, Copy)]
+pub struct AmostradorPonte {
+    /// Desvio padrão do comprimento da "ponte" (em pixels); deve ser da ordem da largura das portas
+    pub sigma: f64,
+}
+
+impl Sampler for AmostradorPonte {
+    fn sample(&mut self, map: &OccupancyMap, rng: &mut dyn RngCore) -> Option<Point> {
+        let p1 = ponto_nos_limites(map, rng);
+        if !map.is_obstructed(&p1) {
+            return None;
+        }
+        let p2 = vizinho_gaussiano(&p1, self.sigma, rng);
+        // Fora dos limites conta como obstáculo
+        if dentro_dos_limites(map, &p2) && !map.is_obstructed(&p2) {
+            return None;
+        }
+        let meio = Point { x: (p1.x + p2.x) / 2.0, y: (p1.y + p2.y) / 2.0 };
+        if map.is_obstructed(&meio) { None } else { Some(meio) }
+    }
+}
+
+/// Amostragem baseada em obstáculo (OBPRM): sorteia um ponto dentro de um obstáculo e anda numa direção
+/// aleatória até sair dele, retornando o primeiro ponto livre (na superfície do obstáculo)
+#[derive(Debug, Clone, Copy)]
+pub struct AmostradorObstaculo {
+    /// Tamanho do passo da caminhada (em pixels)
+    passo: f64,
+}
+
+impl AmostradorObstaculo {
+    /// Cria o amostrador com o passo dado, que precisa ser finito e positivo (com passo 0 a caminhada não sai do lugar)
+    pub fn new(passo: f64) -> Result<Self, String> {
+        if !passo.is_finite() || passo <= 0.0 {
+            return Err(format!("O passo da caminhada precisa ser finito e positivo, veio {}.", passo));
+        }
+        Ok(AmostradorObstaculo { passo })
+    }
+}
+
+impl Sampler for AmostradorObstaculo {
+    fn sample(&mut self, map: &OccupancyMap, rng: &mut dyn RngCore) -> Option<Point> {
+        let mut p = ponto_nos_limites(map, rng);
+        if !map.is_obstructed(&p) {
+            return None;
+        }
+        let angulo = rng.gen_range(0.0..std::f64::consts::TAU);
+        let (dx, dy) = (self.passo * angulo.cos(), self.passo * angulo.sin());
+
+        loop {
+            p = Point { x: p.x + dx, y: p.y + dy };
+            if !dentro_dos_limites(map, &p) {
+                return None;
+            }
+            if !map.is_obstructed(&p) {
+                return Some(p);
+            }
+        }
+    }
+}
+
+/// Amostragem no eixo medial (MAPRM): sorteia um ponto livre, acha o obstáculo mais próximo e
+/// empurra o ponto para longe dele até o obstáculo mais próximo passar a ser outro (o ponto ficou no meio)
+#[derive(Debug, Clone, Copy)]
+pub struct AmostradorEixoMedial {
+    /// Distância máxima (em pixels) em que se procura obstáculo; pontos mais longe que isso são retornados sem mudança
+    pub raio_busca: u32,
+}
+
+/// Pixel obstruído mais próximo de `p`, procurando em quadrados crescentes até `raio_busca`
+fn obstaculo_mais_proximo(map: &OccupancyMap, p: &Point, raio_busca: u32) -> Option<Point> {
+    let (cx, cy) = (p.x.round() as i64, p.y.round() as i64);
+    let mut melhor: Option<(f64, Point)> = None;
+
+    for r in 1..=raio_busca as i64 {
+        // Num quadrado de raio r o pixel mais próximo está a pelo menos r; se já achamos algo mais perto, para
+        if let Some((d, _)) = melhor {
+            if d <= r as f64 {
+                break;
+            }
+        }
+        for dy in -r..=r {
+            for dx in -r..=r {
+                if dx.abs() != r && dy.abs() != r {
+                    continue; // só a borda do quadrado
+                }
+                let q = Point { x: (cx + dx) as f64, y: (cy + dy) as f64 };
+                if q.x < 0.0 || q.y < 0.0 || !map.is_obstructed(&q) {
+                    continue;
+                }
+                let d = q.dist(p);
+                if melhor.map_or(true, |(md, _)| d < md) {
+                    melhor = Some((d, q));
+                }
+            }
+        }
+    }
+    melhor.map(|(_, q)| q)
+}
+
+impl Sampler for AmostradorEixoMedial {
+    fn sample(&mut self, map: &OccupancyMap, rng: &mut dyn RngCore) -> Option<Point> {
+        let mut p = ponto_nos_limites(map, rng);
+        if map.is_obstructed(&p) {
+            return None;
+        }
+        let testemunha = match obstaculo_mais_proximo(map, &p, self.raio_busca) {
+            Some(q) => q,
+            None => return Some(p),
+        };
+
+        // Direção oposta ao obstáculo mais próximo
+        let d = p.dist(&testemunha).max(f64::EPSILON);
+        let (dx, dy) = ((p.x - testemunha.x) / d, (p.y - testemunha.y) / d);
+
+        // Anda para longe do obstáculo enquanto ele continuar sendo (quase) o mais próximo.
+        // O passo começa em metade da folga e cai pela metade quando passa do eixo medial, até 1 pixel.
+        let mut passo = (d / 2.0).max(1.0);
+        for _ in 0..(4 * self.raio_busca) {
+            let proximo = Point { x: p.x + passo * dx, y: p.y + passo * dy };
+            let mesmo_lado = dentro_dos_limites(map, &proximo)
+                && !map.is_obstructed(&proximo)
+                && match obstaculo_mais_proximo(map, &proximo, self.raio_busca) {
+                    Some(q) => proximo.dist(&testemunha) <= proximo.dist(&q) + 1.5,
+                    None => false,
+                };
+
+            if mesmo_lado {
+                p = proximo;
+            } else if passo <= 1.0 {
+                // O obstáculo mais próximo mudou (ou sumiu do raio): o ponto está no eixo medial
+                break;
+            } else {
+                passo = (passo / 2.0).max(1.0);
+            }
+        }
+        Some(p)
+    }
+}
+
+/// Combina várias estratégias: a cada amostra, sorteia uma delas com probabilidade proporcional ao peso
+pub struct AmostradorMisto {
+    estrategias: Vec<(Box<dyn Sampler>, f64)>,
+    peso_total: f64,
+}
+
+impl AmostradorMisto {
+    /// Cria a mistura a partir de pares (estratégia, proporção). Proporções não precisam somar 1.
+    pub fn new(estrategias: Vec<(Box<dyn Sampler>, f64)>) -> Result<Self, String> {
+        if estrategias.iter().any(|(_, peso)| !peso.is_finite() || *peso < 0.0) {
+            return Err("As proporções das estratégias precisam ser finitas e não negativas.".to_string());
+        }
+        let peso_total: f64 = estrategias.iter().map(|(_, peso)| peso).sum();
+        if peso_total <= 0.0 {
+            return Err("Pelo menos uma estratégia precisa de proporção maior que zero.".to_string());
+        }
+        Ok(AmostradorMisto { estrategias, peso_total })
+    }
+}
+
+impl Sampler for AmostradorMisto {
+    fn sample(&mut self, map: &OccupancyMap, rng: &mut dyn RngCore) -> Option<Point> {
+        let mut sorteio = rng.gen_range(0.0..self.peso_total);
+        let ultima = self.estrategias.len() - 1;
+        for (i, (estrategia, peso)) in self.estrategias.iter_mut().enumerate() {
+            if sorteio < *peso || i == ultima {
+                return estrategia.sample(map, rng);
+            }
+            sorteio -= *peso;
+        }
+        None
+    }
+}
+
+// --- Amostragem de baixa discrepância ---
+
+/// Inverso radical de `indice` na `base`: espelha os dígitos de `indice` depois da vírgula (ex.: base 2, 6 = 110 -> 0.011)
+fn inverso_radical(mut indice: u64, base: u64) -> f64 {
+    let mut resultado = 0.0;
+    let mut fator = 1.0 / base as f64;
+    while indice > 0 {
+        resultado += (indice % base) as f64 * fator;
+        indice /= base;
+        fator /= base as f64;
+    }
+    resultado
+}
+
+/// Sequência de Halton: coordenada x é o inverso radical na base 2 e y na base 3.
+/// Determinística; pontos obstruídos são descartados e a sequência segue para o próximo índice.
+#[derive(Debug, Clone, Copy)]
+pub struct AmostradorHalton {
+    /// Próximo índice da sequência (começa em 1, pois o índice 0 é o canto do mapa)
+    pub indice: u64,
+    /// Bases das coordenadas x e y (primos diferentes; conferidas pelo `new`)
+    bases: (u64, u64),
+}
+
+impl AmostradorHalton {
+    /// Cria a sequência com as bases dadas, que precisam ser pelo menos 2 (base 1 não anda e base 0 divide
+    /// por zero) e diferentes entre si. Para cobrir bem o mapa, use primos.
+    pub fn new(bases: (u64, u64)) -> Result<Self, String> {
+        if bases.0 < 2 || bases.1 < 2 || bases.0 == bases.1 {
+            return Err(format!("As bases do Halton precisam ser diferentes e pelo menos 2, vieram {:?}.", bases));
+        }
+        Ok(AmostradorHalton { indice: 1, bases })
+    }
+}
+
+impl Default for AmostradorHalton {
+    fn default() -> Self {
+        AmostradorHalton { indice: 1, bases: (2, 3) }
+    }
+}
+
+impl Sampler for AmostradorHalton {
+    fn sample(&mut self, map: &OccupancyMap, _rng: &mut dyn RngCore) -> Option<Point> {
+        let u = inverso_radical(self.indice, self.bases.0);
+        let v = inverso_radical(self.indice, self.bases.1);
+        self.indice += 1;
+
+        let p = escalar_para_limites(map, u, v);
+        if map.is_obstructed(&p) { None } else { Some(p) }
+    }
+}
+
+/// Quantidade de bits dos números de direção do Sobol
+const BITS_SOBOL: usize = 32;
+
+/// Sequência de Sobol nas duas primeiras dimensões. A primeira é o inverso radical na base 2 e a segunda
+/// usa o polinômio primitivo x + 1, cujos números de direção seguem m_k = 2 m_(k-1) XOR m_(k-1), com m_1 = 1.
+/// Determinística; pontos obstruídos são descartados e a sequência segue para o próximo índice.
+#[derive(Debug, Clone)]
+pub struct AmostradorSobol {
+    /// Próximo índice da sequência (começa em 1, pois o índice 0 é o canto do mapa)
+    pub indice: u32,
+    /// Números de direção da segunda dimensão, já deslocados para 32 bits
+    direcoes: [u32; BITS_SOBOL],
+}
+
+impl Default for AmostradorSobol {
+    fn default() -> Self {
+        let mut direcoes = [0u32; BITS_SOBOL];
+        let mut m: u64 = 1;
+        for k in 1..=BITS_SOBOL {
+            if k > 1 {
+                m = (m << 1) ^ m;
+            }
+            // v_k = m_k / 2^k, guardado como inteiro de 32 bits
+            direcoes[k - 1] = (m << (BITS_SOBOL - k)) as u32;
+        }
+        AmostradorSobol { indice: 1, direcoes }
+    }
+}
+
+impl Sampler for AmostradorSobol {
+    fn sample(&mut self, map: &OccupancyMap, _rng: &mut dyn RngCore) -> Option<Point> {
+        let i = self.indice;
+        self.indice = self.indice.wrapping_add(1).max(1);
+
+        // Primeira dimensão: bits do índice espelhados; segunda: XOR das direções dos bits ligados
+        let x = i.reverse_bits();
+        let mut y = 0u32;
+        for k in 0..BITS_SOBOL {
+            if (i >> k) & 1 == 1 {
+                y ^= self.direcoes[k];
+            }
+        }
+
+        let escala = (1u64 << BITS_SOBOL) as f64;
+        let p = escalar_para_limites(map, x as f64 / escala, y as f64 / escala);
+        if map.is_obstructed(&p) { None } else { Some(p) }
+    }
+}
+
+/// Grade com jitter (amostragem estratificada): divide os limites do mapa em células de mesma área,
+/// uma por amostra pedida, e sorteia um ponto dentro de cada célula, percorrendo as células em ordem.
+/// Depois da última célula volta para a primeira.
+#[derive(Debug, Clone, Copy)]
+pub struct AmostradorGradeJitter {
+    pub colunas: usize,
+    pub linhas: usize,
+    /// Próxima célula a ser amostrada
+    pub indice: usize,
+}
+
+impl AmostradorGradeJitter {
+    /// Cria uma grade com aproximadamente `num_amostras` células quadradas cobrindo os limites do mapa
+    pub fn new(map: &OccupancyMap, num_amostras: usize) -> Self {
+        let (x_min, y_min, x_max, y_max) = map.pixel_bounds;
+        let largura = (x_max - x_min).max(1) as f64;
+        let altura = (y_max - y_min).max(1) as f64;
+        let lado = (largura * altura / num_amostras.max(1) as f64).sqrt();
+
+        AmostradorGradeJitter {
+            colunas: (largura / lado).round().max(1.0) as usize,
+            linhas: (altura / lado).round().max(1.0) as usize,
+            indice: 0,
+        }
+    }
+}
+
+impl Sampler for AmostradorGradeJitter {
+    fn sample(&mut self, map: &OccupancyMap, rng: &mut dyn RngCore) -> Option<Point> {
+        let celula = self.indice % (self.colunas * self.linhas);
+        self.indice += 1;
+
+        let (coluna, linha) = (celula % self.colunas, celula / self.colunas);
+        let u = (coluna as f64 + rng.gen_range(0.0..1.0)) / self.colunas as f64;
+        let v = (linha as f64 + rng.gen_range(0.0..1.0)) / self.linhas as f64;
+
+        let p = escalar_para_limites(map, u, v);
+        if map.is_obstructed(&p) { None } else { Some(p) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readmap::mapa_de_teste;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Sala 100x60 com moldura e dois blocos (y de 20 a 39) separados por uma fresta de 4 pixels (x de 48 a 51)
+    fn sala_com_fresta() -> OccupancyMap {
+        mapa_de_teste(100, 60, |x, y| {
+            let bloco = (25..=47).contains(&x) || (52..=74).contains(&x);
+            x == 0 || y == 0 || x == 99 || y == 59 || (bloco && (20..=39).contains(&y))
+        })
+    }
+
+    /// Junta `n` amostras bem-sucedidas (desistindo depois de muitas tentativas)
+    fn amostrar(amostrador: &mut dyn Sampler, map: &OccupancyMap, n: usize, semente: u64) -> Vec<Point> {
+        let mut rng = StdRng::seed_from_u64(semente);
+        let mut pontos = Vec::new();
+        for _ in 0..1_000_000 {
+            if pontos.len() == n {
+                break;
+            }
+            if let Some(p) = amostrador.sample(map, &mut rng) {
+                pontos.push(p);
+            }
+        }
+        assert_eq!(pontos.len(), n, "o amostrador quase nunca acerta um ponto");
+        pontos
+    }
+
+    /// Distância até o pixel de obstáculo mais próximo
+    fn folga(map: &OccupancyMap, p: &Point) -> f64 {
+        obstaculo_mais_proximo(map, p, 60).map_or(f64::INFINITY, |q| q.dist(p))
+    }
+
+    fn todos() -> Vec<(&'static str, Box<dyn Sampler>)> {
+        vec![
+            ("uniforme", Box::new(AmostradorUniforme)),
+            ("gaussiano", Box::new(AmostradorGaussiano { sigma: 3.0 })),
+            ("ponte", Box::new(AmostradorPonte { sigma: 3.0 })),
+            ("obstaculo", Box::new(AmostradorObstaculo::new(1.0).unwrap())),
+            ("eixo medial", Box::new(AmostradorEixoMedial { raio_busca: 30 })),
+        ]
+    }
+
+    #[test]
+    fn nenhum_ponto_obstruido() {
+        let map = sala_com_fresta();
+        for (nome, mut amostrador) in todos() {
+            for p in amostrar(amostrador.as_mut(), &map, 300, 7) {
+                assert!(!map.is_obstructed(&p) && dentro_dos_limites(&map, &p), "{}: {:?}", nome, p);
+            }
+        }
+    }
+
+    #[test]
+    fn mesma_semente_mesmas_amostras() {
+        let map = sala_com_fresta();
+        for ((nome, mut a), (_, mut b)) in todos().into_iter().zip(todos()) {
+            assert_eq!(amostrar(a.as_mut(), &map, 50, 11), amostrar(b.as_mut(), &map, 50, 11), "{}", nome);
+        }
+    }
+
+    #[test]
+    fn gaussiano_ponte_e_obstaculo_ficam_perto_dos_obstaculos() {
+        let map = sala_com_fresta();
+        let sigma = 3.0;
+        let media = |pontos: &[Point]| pontos.iter().map(|p| folga(&map, p)).sum::<f64>() / pontos.len() as f64;
+        let media_uniforme = media(&amostrar(&mut AmostradorUniforme, &map, 300, 3));
+
+        for (nome, mut amostrador, limite) in [
+            ("gaussiano", Box::new(AmostradorGaussiano { sigma }) as Box<dyn Sampler>, 4.0 * sigma + 1.0),
+            ("ponte", Box::new(AmostradorPonte { sigma }), 3.0 * sigma),
+            // O ponto anterior da caminhada arredonda para um pixel de obstáculo
+            ("obstaculo", Box::new(AmostradorObstaculo::new(1.0).unwrap()), 2.0),
+        ] {
+            let pontos = amostrar(amostrador.as_mut(), &map, 300, 3);
+            let pior = pontos.iter().map(|p| folga(&map, p)).fold(0.0, f64::max);
+            assert!(pior <= limite, "{}: ponto a {:.2} do obstáculo mais próximo", nome, pior);
+            assert!(media(&pontos) < media_uniforme / 2.0, "{}: folga média {:.2}, uniforme {:.2}", nome, media(&pontos), media_uniforme);
+        }
+    }
+
+    #[test]
+    fn eixo_medial_centraliza_no_corredor() {
+        // Corredor horizontal de y = 10 a y = 29: o eixo medial é a reta y = 19.5
+        let map = mapa_de_teste(80, 40, |x, y| x == 0 || x == 79 || y < 10 || y > 29);
+        for p in amostrar(&mut AmostradorEixoMedial { raio_busca: 30 }, &map, 100, 5) {
+            // Perto das pontas do corredor a parede lateral é o obstáculo mais próximo
+            if p.x > 12.0 && p.x < 67.0 {
+                assert!((p.y - 19.5).abs() <= 1.5, "{:?} longe do eixo", p);
+            }
+        }
+    }
+
+    /// Estratégia de teste que sempre devolve o mesmo ponto
+    struct Fixo(Point);
+
+    impl Sampler for Fixo {
+        fn sample(&mut self, _: &OccupancyMap, _: &mut dyn RngCore) -> Option<Point> {
+            Some(self.0)
+        }
+    }
+
+    #[test]
+    fn misto_respeita_as_proporcoes() {
+        let map = sala_com_fresta();
+        let (a, b) = (Point { x: 5.0, y: 5.0 }, Point { x: 50.0, y: 30.0 });
+        let mut misto = AmostradorMisto::new(vec![(Box::new(Fixo(a)), 3.0), (Box::new(Fixo(b)), 1.0), (Box::new(AmostradorUniforme), 0.0)]).unwrap();
+        let pontos = amostrar(&mut misto, &map, 4000, 13);
+        let de_a = pontos.iter().filter(|&&p| p == a).count();
+        let de_b = pontos.iter().filter(|&&p| p == b).count();
+        assert_eq!(de_a + de_b, 4000, "a estratégia de peso zero nunca deve ser sorteada");
+        assert!((de_a as f64 / 4000.0 - 0.75).abs() < 0.03, "{} de 4000 vieram da primeira estratégia", de_a);
+    }
+
+    #[test]
+    fn obstaculo_recusa_passo_invalido() {
+        for passo in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(AmostradorObstaculo::new(passo).is_err(), "aceitou passo {}", passo);
+        }
+    }
+
+    #[test]
+    fn misto_recusa_proporcoes_invalidas() {
+        assert!(AmostradorMisto::new(vec![]).is_err());
+        assert!(AmostradorMisto::new(vec![(Box::new(AmostradorUniforme), 0.0)]).is_err());
+        assert!(AmostradorMisto::new(vec![(Box::new(AmostradorUniforme), -1.0)]).is_err());
+        assert!(AmostradorMisto::new(vec![(Box::new(AmostradorUniforme), f64::NAN)]).is_err());
+    }
+
+    /// Mapa 11x11 sem obstáculos: os limites vão de 0 a 10, então o ponto é 10 vezes a coordenada no quadrado unitário
+    fn mapa_livre() -> OccupancyMap {
+        mapa_de_teste(11, 11, |_, _| false)
+    }
+
+    fn perto(p: Point, x: f64, y: f64) -> bool {
+        (p.x - x).abs() < 1e-9 && (p.y - y).abs() < 1e-9
+    }
+
+    #[test]
+    fn inverso_radical_espelha_os_digitos() {
+        let base2: Vec<f64> = (1..=4).map(|i| inverso_radical(i, 2)).collect();
+        assert_eq!(base2, vec![0.5, 0.25, 0.75, 0.125]);
+        assert!((inverso_radical(1, 3) - 1.0 / 3.0).abs() < 1e-12);
+        assert!((inverso_radical(5, 3) - 7.0 / 9.0).abs() < 1e-12);
+        assert_eq!(inverso_radical(0, 2), 0.0);
+    }
+
+    #[test]
+    fn primeiros_termos_de_halton_e_sobol() {
+        let map = mapa_livre();
+        let halton = amostrar(&mut AmostradorHalton::default(), &map, 3, 0);
+        assert!(perto(halton[0], 5.0, 10.0 / 3.0) && perto(halton[1], 2.5, 20.0 / 3.0) && perto(halton[2], 7.5, 10.0 / 9.0), "{:?}", halton);
+
+        let sobol = amostrar(&mut AmostradorSobol::default(), &map, 4, 0);
+        let esperado = [(0.5, 0.5), (0.25, 0.75), (0.75, 0.25), (0.125, 0.625)];
+        for (p, (u, v)) in sobol.iter().zip(esperado) {
+            assert!(perto(*p, 10.0 * u, 10.0 * v), "{:?}", sobol);
+        }
+    }
+
+    #[test]
+    fn halton_confere_as_bases() {
+        for bases in [(0, 3), (1, 3), (2, 2), (5, 1)] {
+            assert!(AmostradorHalton::new(bases).is_err(), "aceitou as bases {:?}", bases);
+        }
+        let map = mapa_livre();
+        assert_eq!(
+            amostrar(&mut AmostradorHalton::new((2, 3)).unwrap(), &map, 50, 0),
+            amostrar(&mut AmostradorHalton::default(), &map, 50, 0)
+        );
+    }
+
+    #[test]
+    fn halton_e_sobol_nao_dependem_da_semente() {
+        let map = sala_com_fresta();
+        assert_eq!(amostrar(&mut AmostradorHalton::default(), &map, 100, 1), amostrar(&mut AmostradorHalton::default(), &map, 100, 2));
+        assert_eq!(amostrar(&mut AmostradorSobol::default(), &map, 100, 1), amostrar(&mut AmostradorSobol::default(), &map, 100, 2));
+    }
+
+    #[test]
+    fn baixa_discrepancia_nao_retorna_ponto_obstruido() {
+        let map = sala_com_fresta();
+        let mut amostradores: Vec<Box<dyn Sampler>> =
+            vec![Box::new(AmostradorHalton::default()), Box::new(AmostradorSobol::default()), Box::new(AmostradorGradeJitter::new(&map, 200))];
+        for amostrador in amostradores.iter_mut() {
+            for p in amostrar(amostrador.as_mut(), &map, 300, 9) {
+                assert!(!map.is_obstructed(&p) && dentro_dos_limites(&map, &p), "{:?}", p);
+            }
+        }
+    }
+
+    #[test]
+    fn grade_jitter_cobre_uma_amostra_por_celula() {
+        let map = mapa_livre();
+        let mut grade = AmostradorGradeJitter::new(&map, 25);
+        assert_eq!((grade.colunas, grade.linhas), (5, 5));
+
+        let pontos = amostrar(&mut grade, &map, 25, 4);
+        for (i, p) in pontos.iter().enumerate() {
+            let (coluna, linha) = (i % 5, i / 5);
+            assert_eq!(((p.x / 2.0) as usize, (p.y / 2.0) as usize), (coluna, linha), "amostra {} fora da célula", i);
+        }
+        // Depois da última célula volta para a primeira
+        let proximo = amostrar(&mut grade, &map, 1, 5)[0];
+        assert!(proximo.x < 2.0 && proximo.y < 2.0);
+    }
+}
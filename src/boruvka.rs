@@ -0,0 +1,111 @@
+
+//! #  Algoritmo de Borůvka paralelo para a árvore geradora mínima
+//! Em cada rodada, cada componente escolhe sua aresta de saída mais barata. A busca dessas arestas é dividida
+//! entre várias threads, o que ajuda em roadmaps muito grandes (centenas de milhares de vértices).
+//! Empates de peso são desfeitos pelo par de índices (menor, maior), a mesma ordem do `UndirEdge` usada pelo `kruskal`,
+//! então as duas implementações geram a mesma árvore.
+
+#![allow(warnings)]
+
+use crate::structs::{Graph, Peso};
+use crate::dsu::DSU;
+use std::collections::HashMap;
+use std::thread;
+
+/// Aresta candidata de uma componente: (peso, menor índice, maior índice).
+/// A ordem da tupla é a mesma do `UndirEdge`, usada pelo `kruskal`.
+type Candidata = (Peso, usize, usize);
+
+/// Procura, para os vértices de `inicio..fim`, a aresta mais barata que sai de cada componente
+fn menores_arestas(gr: &Graph, comp: &[usize], inicio: usize, fim: usize) -> HashMap<usize, Candidata> {
+    let mut melhores: HashMap<usize, Candidata> = HashMap::new();
+
+    for u in inicio..fim {
+        for edge in &gr.adj[u] {
+            let v = edge.to_idx;
+            /// Arestas dentro da mesma componente formariam ciclo
+            if comp[u] == comp[v] {
+                continue;
+            }
+            let candidata = (edge.weight, u.min(v), u.max(v));
+            let atual = melhores.entry(comp[u]).or_insert(candidata);
+            if candidata < *atual {
+                *atual = candidata;
+            }
+        }
+    }
+
+    melhores
+}
+
+/// Retorna uma Árvore Geradora Minima usando o algoritmo de Borůvka com `num_threads` threads.
+/// Mesmo contrato do `kruskal`: a árvore recebe todos os vértices do grafo original e,
+/// se o grafo não for conexo, retorna um erro com a quantidade de componentes.
+pub fn boruvka(gr: &Graph, num_threads: usize) -> Result<Graph, String> {
+    let n = gr.vertices.len();
+    let num_threads = num_threads.max(1);
+
+    /// Inicializa a Disjoint Set Union que guarda as componentes já unidas
+    let mut dsu = DSU::new(n);
+    /// Inicializa a Árvore Geradora Minima com TODOS os vértices do grafo original
+    let mut agm = Graph::new();
+    for &v in &gr.vertices {
+        agm.add_vertex(v);
+    }
+
+    loop {
+        /// Raíz de cada vértice nesta rodada (o find precisa de &mut, então é feito antes das threads)
+        let comp: Vec<usize> = (0..n).map(|v| dsu.find(v)).collect();
+
+        /// Cada thread procura as menores arestas de um bloco de vértices
+        let tamanho_bloco = n.div_ceil(num_threads).max(1);
+        let parciais: Vec<HashMap<usize, Candidata>> = thread::scope(|s| {
+            let handles: Vec<_> = (0..n)
+                .step_by(tamanho_bloco)
+                .map(|inicio| {
+                    let fim = (inicio + tamanho_bloco).min(n);
+                    let comp = &comp;
+                    s.spawn(move || menores_arestas(gr, comp, inicio, fim))
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().expect("Thread do Borůvka falhou.")).collect()
+        });
+
+        /// Junta os resultados das threads ficando com a menor aresta de cada componente
+        let mut melhores: HashMap<usize, Candidata> = HashMap::new();
+        for parcial in parciais {
+            for (raiz, candidata) in parcial {
+                let atual = melhores.entry(raiz).or_insert(candidata);
+                if candidata < *atual {
+                    *atual = candidata;
+                }
+            }
+        }
+
+        /// Percorre as candidatas em ordem para a saída não depender da ordem do HashMap
+        let mut candidatas: Vec<Candidata> = melhores.into_values().collect();
+        candidatas.sort();
+        candidatas.dedup_by(|a, b| (a.1, a.2) == (b.1, b.2));
+
+        let mut adicionadas = 0;
+        for (weight, u, v) in candidatas {
+            /// Duas componentes podem ter escolhido arestas que fecham um ciclo entre si; o DSU evita isso
+            if dsu.union(u, v) {
+                agm.add_edge_peso(u, v, weight)?;
+                adicionadas += 1;
+            }
+        }
+
+        /// Sem arestas novas, todas as componentes restantes estão isoladas umas das outras
+        if adicionadas == 0 {
+            break;
+        }
+    }
+
+    // Se sobrou mais de uma componente, o grafo não é conexo
+    if dsu.num_componentes() > 1 {
+        return Err(format!("O grafo não é conexo. Existem {} componentes.", dsu.num_componentes()));
+    }
+
+    Ok(agm)
+}
@@ -0,0 +1,145 @@
+
+//! #  Camada de custo do terreno
+//! Uma imagem opcional, do mesmo tamanho do mapa, que dá o custo de atravessar cada pixel (grama, cascalho,
+//! estrada...). O custo é um multiplicador do comprimento: 1 é o terreno mais barato e não pode ser menor que 1,
+//! para as heurísticas dos planejadores em grade continuarem admissíveis.
+//! - `FonteCusto::Intensidade`: a luminância do pixel é levada linearmente para [minimo, maximo]
+//! - `FonteCusto::Legenda`: cada cor da legenda tem um custo; as outras cores recebem o custo padrão
+//! ## Output esperado
+//! O custo de cada pixel (`Vec<f64>`, indexado por `y * largura + x`), usado pelo `custo_pixel` do
+//! `OccupancyMap` e, por ele, pelo `custo_segmento` (pesos do PRM, AGM, Dijkstra) e pelos planejadores em grade.
+
+#![allow(warnings)]
+
+use crate::classificador::luminancia;
+use image::DynamicImage;
+
+/// Como ler o custo da imagem
+#[derive(Debug, Clone, PartialEq)]
+pub enum FonteCusto {
+    /// Preto = `minimo` e branco = `maximo` (o contrário se `invertido`)
+    Intensidade { minimo: f64, maximo: f64, invertido: bool },
+    /// Lista de (cor RGB, custo). Uma cor casa se cada canal diferir no máximo `tolerancia`; vale a primeira
+    /// da lista que casar. Pixels sem cor na legenda recebem `padrao`.
+    Legenda { cores: Vec<([u8; 3], f64)>, tolerancia: u8, padrao: f64 },
+}
+
+/// Erro se o custo não for finito ou for menor que 1
+pub(crate) fn conferir_custo(custo: f64, nome: &str) -> Result<(), String> {
+    if !custo.is_finite() || custo < 1.0 {
+        return Err(format!("O custo {} precisa ser finito e pelo menos 1, veio {}.", nome, custo));
+    }
+    Ok(())
+}
+
+impl FonteCusto {
+    fn conferir(&self) -> Result<(), String> {
+        match self {
+            FonteCusto::Intensidade { minimo, maximo, .. } => {
+                conferir_custo(*minimo, "mínimo")?;
+                conferir_custo(*maximo, "máximo")
+            }
+            FonteCusto::Legenda { cores, padrao, .. } => {
+                for (cor, custo) in cores {
+                    conferir_custo(*custo, &format!("da cor {:?}", cor))?;
+                }
+                conferir_custo(*padrao, "padrão")
+            }
+        }
+    }
+
+    fn custo(&self, [r, g, b]: [u8; 3]) -> f64 {
+        match self {
+            FonteCusto::Intensidade { minimo, maximo, invertido } => {
+                let mut t = luminancia(r, g, b) as f64 / 255.0;
+                if *invertido {
+                    t = 1.0 - t;
+                }
+                minimo + (maximo - minimo) * t
+            }
+            FonteCusto::Legenda { cores, tolerancia, padrao } => cores
+                .iter()
+                .find(|(cor, _)| {
+                    cor.iter().zip([r, g, b]).all(|(&c, p)| c.abs_diff(p) <= *tolerancia)
+                })
+                .map(|&(_, custo)| custo)
+                .unwrap_or(*padrao),
+        }
+    }
+}
+
+/// Lê o custo de cada pixel da imagem (indexado por `y * largura + x`)
+pub fn ler_camada_custo(img: &DynamicImage, fonte: &FonteCusto) -> Result<Vec<f64>, String> {
+    fonte.conferir()?;
+    Ok(img.to_rgb8().pixels().map(|p| fonte.custo(p.0)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grade::a_estrela_grade;
+    use crate::prm_generator::passos_colisao;
+    use crate::readmap::mapa_de_teste;
+    use crate::structs::Point;
+    use image::{ImageBuffer, Rgb};
+
+    fn imagem(cores: &[[u8; 3]]) -> DynamicImage {
+        DynamicImage::ImageRgb8(ImageBuffer::from_fn(cores.len() as u32, 1, |x, _| Rgb(cores[x as usize])))
+    }
+
+    #[test]
+    fn intensidade_vai_do_minimo_ao_maximo() {
+        let img = imagem(&[[0, 0, 0], [51, 51, 51], [255, 255, 255]]);
+        let custo = ler_camada_custo(&img, &FonteCusto::Intensidade { minimo: 1.0, maximo: 6.0, invertido: false }).unwrap();
+        assert_eq!(custo, vec![1.0, 2.0, 6.0]);
+        let custo = ler_camada_custo(&img, &FonteCusto::Intensidade { minimo: 1.0, maximo: 6.0, invertido: true }).unwrap();
+        assert_eq!(custo, vec![6.0, 5.0, 1.0]);
+    }
+
+    #[test]
+    fn legenda_usa_a_primeira_cor_que_casa() {
+        let img = imagem(&[[0, 200, 0], [5, 195, 3], [200, 0, 0], [0, 0, 255]]);
+        let fonte = FonteCusto::Legenda {
+            cores: vec![([0, 200, 0], 2.0), ([0, 198, 0], 7.0), ([200, 0, 0], 10.0)],
+            tolerancia: 5,
+            padrao: 1.5,
+        };
+        assert_eq!(ler_camada_custo(&img, &fonte).unwrap(), vec![2.0, 2.0, 10.0, 1.5]);
+    }
+
+    #[test]
+    fn custos_invalidos_sao_recusados() {
+        let img = imagem(&[[0, 0, 0]]);
+        for fonte in [
+            FonteCusto::Intensidade { minimo: 0.5, maximo: 2.0, invertido: false },
+            FonteCusto::Intensidade { minimo: 1.0, maximo: f64::INFINITY, invertido: false },
+            FonteCusto::Legenda { cores: vec![([0, 0, 0], f64::NAN)], tolerancia: 0, padrao: 1.0 },
+            FonteCusto::Legenda { cores: vec![], tolerancia: 0, padrao: 0.0 },
+        ] {
+            assert!(ler_camada_custo(&img, &fonte).is_err(), "aceitou {:?}", fonte);
+        }
+    }
+
+    #[test]
+    fn desvio_barato_vence_a_reta_cara() {
+        // Mapa 40x30 todo livre; o retângulo x 15..=24, y 5..=24 é lama (custo 10) e corta a reta entre os extremos
+        let mut map = mapa_de_teste(40, 30, |_, _| false);
+        let lama = |x: u32, y: u32| (15..=24).contains(&x) && (5..=24).contains(&y);
+        let camada = DynamicImage::ImageRgb8(ImageBuffer::from_fn(40, 30, |x, y| if lama(x, y) { Rgb([120, 80, 0]) } else { Rgb([0, 160, 0]) }));
+        let fonte = FonteCusto::Legenda { cores: vec![([120, 80, 0], 10.0)], tolerancia: 0, padrao: 1.0 };
+        assert!(map.definir_camada_custo(&imagem(&[[0, 0, 0]]), &fonte).is_err());
+        map.definir_camada_custo(&camada, &fonte).unwrap();
+
+        let (inicio, fim) = (Point { x: 2.0, y: 15.0 }, Point { x: 37.0, y: 15.0 });
+        let reta = map.custo_segmento(&inicio, &fim, passos_colisao(inicio.dist(&fim)));
+        assert!(reta > 35.0 + 9.0 * 9.0, "a reta deveria pagar a lama: {}", reta);
+
+        let resultado = a_estrela_grade(&map, inicio, fim).unwrap();
+        assert!(resultado.custo < reta);
+        assert!(resultado.caminho.iter().all(|p| !lama(p.x as u32, p.y as u32)), "o caminho atravessa a lama");
+
+        // Sem a camada, volta a ser a reta
+        map.remover_camada_custo();
+        assert!((a_estrela_grade(&map, inicio, fim).unwrap().custo - 35.0).abs() < 1e-9);
+    }
+}
@@ -2,8 +2,8 @@
 //! ## Output esperado
 //! Arquivo CSV contendo o grafo e sua arvore geradora mínima em /data. O CSV está estruturado no ponto x y do vértice original, x y do vérfice destino e o peso da aresta.\
 //! ## Algortimo utilizado
-//! PRM para gerar o grafo aleatório;\
-//! Kruskal para gerar a orvore geradora mínima.\
+//! PRM para gerar o grafo aleatório (por raio de conexão, kPRM com os k vizinhos mais próximos ou PRM*);\
+//! Kruskal para gerar a orvore geradora mínima (ou Prim/Borůvka, escolhido pelo nome em `algoritmo_agm`).\
 //! ### Motivação
 //! Apesar da atividade pedir um grafo de visibilidade, optamos por um PRM para conseguirmos lidar com obstáculos não poligonais e Kruskal para o usuário ter retorno se o grafo é conexo, e se não for gerar outro grafo.
 
@@ -12,8 +12,10 @@
 
 pub use crate::structs;
 pub use crate::readmap::OccupancyMap;
-pub use crate::prm_generator::{generate_random_graph, save_graph_to_csv};
+pub use crate::prm_generator::{generate_random_graph, generate_prm, save_graph_to_csv, ModoConexao};
 pub use crate::kruskal::kruskal;
+pub use crate::agm::gerar_agm;
+pub use crate::validacao::validate;
 use std::io;
 
 /// Gera um grafo aleatório para o mapa e sua árvore geradora minima e salva ambos como .csv em /data
@@ -26,18 +28,29 @@ pub fn first_case() {
 
         let num_vertices = 300;   // Quantos nós aleatórios gerar, VALOR ORIGINAL = 250
         let connection_radius = 80.0; // Distância máx. para tentar conectar (em pixels), VALOR ORIGINAL = 60
+        // Ou kPRM: ModoConexao::KVizinhos { k: 10, mutuo: false }; ou PRM* (raio pelo nº de amostras): ModoConexao::RaioPrmEstrela
+        let modo_conexao = ModoConexao::Raio(connection_radius).resolver(&map, num_vertices);
+        println!("Modo de conexão: {:?}", modo_conexao);
+        let algoritmo_agm = "kruskal"; // Algoritmo da AGM: "kruskal", "prim" ou "boruvka"
     
         // --- 3. Gerar o Grafo Aleatório ---
-        let random_graph = generate_random_graph(&map, num_vertices, connection_radius);
+        let random_graph = generate_prm(&map, num_vertices, modo_conexao);
         println!("Grafo aleatório gerado com {} vértices.", random_graph.vertices.len());
 
+        if let Err(violacoes) = validate(&random_graph, modo_conexao.raio_maximo()) {
+            eprintln!("O grafo gerado viola {} invariantes:", violacoes.len());
+            for v in &violacoes {
+                eprintln!("  - {}", v);
+            }
+        }
+
         let graph_csv_path = "data/graph.csv";
         match save_graph_to_csv(&random_graph, graph_csv_path) {
             Ok(_) => println!("Grafo aleatório completo salvo em {}", graph_csv_path),
             Err(e) => eprintln!("Erro ao salvar o grafo completo em CSV: {}", e),
         }
 
-        let agm = match kruskal(&random_graph) {
+        let agm = match gerar_agm(algoritmo_agm, &random_graph) {
             Ok(g) => g,
             Err(e) => {
                 eprintln!("{}", e);
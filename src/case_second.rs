@@ -5,6 +5,7 @@
 pub use crate::structs;
 pub use crate::readmap::OccupancyMap;
 pub use crate::read_graph::read_graph;
+pub use crate::validacao::validate;
 pub use crate::read_coord::read_coord;
 pub use crate::vertice_mais_proximo::vertice_mais_prox;
 pub use crate::dfs::{dfs_path, path_export};
@@ -27,6 +28,15 @@ pub fn second_case() {
         }
     };
 
+    // Confere se o grafo lido é consistente antes de usar
+    if let Err(violacoes) = validate(&agm, None) {
+        eprintln!("O grafo lido de {} viola {} invariantes:", agm_csv_path, violacoes.len());
+        for v in &violacoes {
+            eprintln!("  - {}", v);
+        }
+        return;
+    }
+
     loop {
         println!("Digite o ponto de partida (x y):");
         let (x1, y1) = read_coord();
@@ -0,0 +1,282 @@
+
+//! #  Classificação de pixels da imagem em obstáculo ou livre
+//! Substitui o limiar fixo do `is_black` por um classificador configurável: limiar por canal RGB,
+//! limiar de luminância, limiar automático de Otsu, tratamento do canal alfa (PNG transparente)
+//! e mapas invertidos (branco = obstáculo). Opcionalmente, uma faixa de cinza vira célula desconhecida
+//! (regiões não exploradas dos mapas de SLAM).
+//! ## Output esperado
+//! O estado de cada pixel (`Vec<EstadoCelula>`, indexado por `y * largura + x`) usado pelo `OccupancyMap`.
+
+#![allow(warnings)]
+
+use image::{DynamicImage, Rgba};
+
+/// Abaixo desse alfa o pixel é considerado transparente pelas políticas `TransparenteLivre`/`TransparenteOcupado`
+pub const LIMIAR_ALFA: u8 = 128;
+
+/// Como decidir se a cor de um pixel é escura (obstáculo num mapa normal)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModoLimiar {
+    /// Escuro se os três canais forem menores que o limiar (o comportamento antigo, com 50)
+    Rgb(u8),
+    /// Escuro se a luminância (0.299 R + 0.587 G + 0.114 B) for menor que o limiar
+    Luminancia(u8),
+    /// Limiar de luminância escolhido automaticamente pelo método de Otsu
+    Otsu,
+    /// Modo do map_server do ROS: a probabilidade de ocupação é p = (255 - média dos canais) / 255
+    /// (ou média / 255 se `invertido`). Ocupado se p > `ocupado`, livre se p < `livre`, senão desconhecido.
+    Probabilidade { ocupado: f64, livre: f64 },
+}
+
+/// Estado de um pixel do mapa
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EstadoCelula {
+    Livre,
+    Ocupado,
+    /// Região não explorada: nem livre nem parede. Os planejadores decidem pela `PoliticaDesconhecido` do mapa.
+    Desconhecido,
+}
+
+/// Como os planejadores tratam as células desconhecidas
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PoliticaDesconhecido {
+    /// Desconhecido é obstáculo
+    Proibir,
+    /// Desconhecido é atravessável, mas cada pixel custa o multiplicador dado (finito e >= 1, conferido pelo
+    /// `definir_politica_desconhecido` do mapa)
+    PermitirComCusto(f64),
+    /// Desconhecido é livre, com o mesmo custo
+    Otimista,
+}
+
+/// O que fazer com o canal alfa
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PoliticaAlfa {
+    /// Usa só a cor, como se a imagem fosse opaca
+    Ignorar,
+    /// Mistura a cor com um fundo branco antes de classificar (bordas suavizadas ficam cinza)
+    ComporSobreBranco,
+    /// Pixels transparentes são livres
+    TransparenteLivre,
+    /// Pixels transparentes são obstáculo
+    TransparenteOcupado,
+    /// Pixels transparentes são desconhecidos
+    TransparenteDesconhecido,
+}
+
+/// Configuração da classificação. O `Default` reproduz o `is_black` (RGB < 50, sem alfa, não invertido,
+/// sem células desconhecidas).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassificadorOcupacao {
+    pub modo: ModoLimiar,
+    pub alfa: PoliticaAlfa,
+    /// Se true, pixels claros é que são obstáculo
+    pub invertido: bool,
+    /// Faixa de luminância (inclusiva) classificada como desconhecida antes do limiar, ex.: `Some((180, 230))`
+    /// para o cinza 205 dos mapas do ROS
+    pub desconhecido: Option<(u8, u8)>,
+}
+
+impl Default for ClassificadorOcupacao {
+    fn default() -> Self {
+        ClassificadorOcupacao { modo: ModoLimiar::Rgb(50), alfa: PoliticaAlfa::Ignorar, invertido: false, desconhecido: None }
+    }
+}
+
+/// Luminância (Rec. 601) de uma cor, arredondada para 0..=255
+pub fn luminancia(r: u8, g: u8, b: u8) -> u8 {
+    (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64).round().min(255.0) as u8
+}
+
+/// Limiar de Otsu de um histograma de 256 níveis: o nível t que maximiza a variância entre as classes
+/// [0, t) e [t, 256). Se vários níveis empatam (histograma com vãos), pega o meio do intervalo empatado.
+/// Retorna 128 se o histograma estiver vazio ou tiver um nível só.
+pub fn limiar_otsu(histograma: &[u64; 256]) -> u8 {
+    let total: u64 = histograma.iter().sum();
+    let soma_total: f64 = histograma.iter().enumerate().map(|(i, &h)| i as f64 * h as f64).sum();
+
+    let (mut peso_fundo, mut soma_fundo) = (0u64, 0.0);
+    let (mut melhor, mut melhor_fim, mut melhor_variancia) = (128usize, 128usize, 0.0);
+    for t in 1..256 {
+        peso_fundo += histograma[t - 1];
+        soma_fundo += (t - 1) as f64 * histograma[t - 1] as f64;
+        let peso_frente = total - peso_fundo;
+        if peso_fundo == 0 || peso_frente == 0 {
+            continue;
+        }
+        let media_fundo = soma_fundo / peso_fundo as f64;
+        let media_frente = (soma_total - soma_fundo) / peso_frente as f64;
+        let variancia = peso_fundo as f64 * peso_frente as f64 * (media_fundo - media_frente).powi(2);
+        if variancia > melhor_variancia {
+            melhor_variancia = variancia;
+            melhor = t;
+            melhor_fim = t;
+        } else if variancia == melhor_variancia && melhor_fim == t - 1 {
+            melhor_fim = t;
+        }
+    }
+    ((melhor + melhor_fim) / 2) as u8
+}
+
+impl ClassificadorOcupacao {
+    /// Cor efetiva do pixel depois da política de alfa, ou `Err(estado)` se o alfa já decide sozinho
+    fn aplicar_alfa(&self, p: &Rgba<u8>) -> Result<[u8; 3], EstadoCelula> {
+        let [r, g, b, a] = p.0;
+        match self.alfa {
+            PoliticaAlfa::Ignorar => Ok([r, g, b]),
+            PoliticaAlfa::ComporSobreBranco => {
+                let compor = |c: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+                Ok([compor(r), compor(g), compor(b)])
+            }
+            PoliticaAlfa::TransparenteLivre if a < LIMIAR_ALFA => Err(EstadoCelula::Livre),
+            PoliticaAlfa::TransparenteOcupado if a < LIMIAR_ALFA => Err(EstadoCelula::Ocupado),
+            PoliticaAlfa::TransparenteDesconhecido if a < LIMIAR_ALFA => Err(EstadoCelula::Desconhecido),
+            _ => Ok([r, g, b]),
+        }
+    }
+
+    /// Limiar de luminância que o modo usa na imagem (só faz diferença para `Otsu`, que depende do histograma)
+    fn limiar_da_imagem(&self, img: &image::RgbaImage) -> Option<u8> {
+        match self.modo {
+            ModoLimiar::Rgb(_) | ModoLimiar::Probabilidade { .. } => None,
+            ModoLimiar::Luminancia(t) => Some(t),
+            ModoLimiar::Otsu => {
+                let mut histograma = [0u64; 256];
+                for p in img.pixels() {
+                    if let Ok([r, g, b]) = self.aplicar_alfa(p) {
+                        let l = luminancia(r, g, b);
+                        if !self.na_faixa_desconhecida(l) {
+                            histograma[l as usize] += 1;
+                        }
+                    }
+                }
+                Some(limiar_otsu(&histograma))
+            }
+        }
+    }
+
+    fn na_faixa_desconhecida(&self, l: u8) -> bool {
+        matches!(self.desconhecido, Some((min, max)) if min <= l && l <= max)
+    }
+
+    /// Classifica todos os pixels da imagem em livre, ocupado ou desconhecido, indexados por `y * largura + x`
+    pub fn classificar_estados(&self, img: &DynamicImage) -> Vec<EstadoCelula> {
+        let rgba = img.to_rgba8();
+        let limiar = self.limiar_da_imagem(&rgba);
+
+        rgba.pixels()
+            .map(|p| match self.aplicar_alfa(p) {
+                Err(estado) => estado,
+                Ok([r, g, b]) => {
+                    if self.na_faixa_desconhecida(luminancia(r, g, b)) {
+                        return EstadoCelula::Desconhecido;
+                    }
+                    if let ModoLimiar::Probabilidade { ocupado, livre } = self.modo {
+                        let media = (r as f64 + g as f64 + b as f64) / 3.0;
+                        let p = if self.invertido { media / 255.0 } else { (255.0 - media) / 255.0 };
+                        return if p > ocupado {
+                            EstadoCelula::Ocupado
+                        } else if p < livre {
+                            EstadoCelula::Livre
+                        } else {
+                            EstadoCelula::Desconhecido
+                        };
+                    }
+                    let escuro = match (self.modo, limiar) {
+                        (ModoLimiar::Rgb(t), _) => r < t && g < t && b < t,
+                        (_, Some(t)) => luminancia(r, g, b) < t,
+                        _ => unreachable!(),
+                    };
+                    if escuro != self.invertido { EstadoCelula::Ocupado } else { EstadoCelula::Livre }
+                }
+            })
+            .collect()
+    }
+
+    /// Classifica todos os pixels da imagem. Retorna a máscara (true = obstáculo), indexada por `y * largura + x`.
+    /// Pixels desconhecidos contam como livres aqui; use `classificar_estados` para distingui-los.
+    pub fn classificar(&self, img: &DynamicImage) -> Vec<bool> {
+        self.classificar_estados(img).into_iter().map(|e| e == EstadoCelula::Ocupado).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgb, RgbaImage};
+
+    #[test]
+    fn otsu_separa_histograma_bimodal() {
+        let mut histograma = [0u64; 256];
+        for nivel in 20..40 {
+            histograma[nivel] = 10;
+        }
+        for nivel in 200..230 {
+            histograma[nivel] = 15;
+        }
+        let t = limiar_otsu(&histograma);
+        // Qualquer nível no vão separa igual; o empate fica no meio do vão [40, 200]
+        assert_eq!(t, 120);
+    }
+
+    #[test]
+    fn otsu_histograma_degenerado() {
+        assert_eq!(limiar_otsu(&[0; 256]), 128);
+        let mut um_nivel = [0u64; 256];
+        um_nivel[90] = 1000;
+        assert_eq!(limiar_otsu(&um_nivel), 128);
+    }
+
+    #[test]
+    fn padrao_igual_ao_is_black() {
+        let img = ImageBuffer::from_fn(16, 16, |x, y| Rgb([(x * 16) as u8, (y * 16) as u8, ((x + y) * 8) as u8]));
+        let esperado: Vec<bool> = img.pixels().map(crate::readmap::is_black).collect();
+        assert_eq!(ClassificadorOcupacao::default().classificar(&DynamicImage::ImageRgb8(img)), esperado);
+    }
+
+    #[test]
+    fn otsu_invertido_e_alfa() {
+        // Metade esquerda escura, metade direita clara e a última coluna transparente
+        let img = RgbaImage::from_fn(8, 2, |x, _| match x {
+            7 => Rgba([0, 0, 0, 0]),
+            0..=3 => Rgba([30, 30, 30, 255]),
+            _ => Rgba([220, 220, 220, 255]),
+        });
+        let img = DynamicImage::ImageRgba8(img);
+
+        let otsu = ClassificadorOcupacao { modo: ModoLimiar::Otsu, alfa: PoliticaAlfa::TransparenteLivre, ..Default::default() };
+        let mascara = otsu.classificar(&img);
+        assert_eq!((mascara[0], mascara[5], mascara[7]), (true, false, false));
+
+        let ocupado = ClassificadorOcupacao { alfa: PoliticaAlfa::TransparenteOcupado, ..otsu };
+        assert!(ocupado.classificar(&img)[7]);
+
+        // Compor sobre branco deixa o pixel transparente claro; ignorar o alfa deixa o preto
+        let branco = ClassificadorOcupacao { alfa: PoliticaAlfa::ComporSobreBranco, ..otsu };
+        assert!(!branco.classificar(&img)[7]);
+        let ignorar = ClassificadorOcupacao { alfa: PoliticaAlfa::Ignorar, ..otsu };
+        assert!(ignorar.classificar(&img)[7]);
+
+        let invertido = ClassificadorOcupacao { invertido: true, ..otsu };
+        let mascara = invertido.classificar(&img);
+        assert_eq!((mascara[0], mascara[5]), (false, true));
+
+        let desconhecido = ClassificadorOcupacao { alfa: PoliticaAlfa::TransparenteDesconhecido, ..otsu };
+        let estados = desconhecido.classificar_estados(&img);
+        assert_eq!((estados[0], estados[5], estados[7]), (EstadoCelula::Ocupado, EstadoCelula::Livre, EstadoCelula::Desconhecido));
+        // Na máscara o desconhecido conta como livre
+        assert!(!desconhecido.classificar(&img)[7]);
+        let estados = ClassificadorOcupacao { invertido: true, ..desconhecido }.classificar_estados(&img);
+        assert_eq!((estados[0], estados[5], estados[7]), (EstadoCelula::Livre, EstadoCelula::Ocupado, EstadoCelula::Desconhecido));
+    }
+
+    #[test]
+    fn faixa_de_cinza_desconhecida() {
+        let img = DynamicImage::ImageRgb8(ImageBuffer::from_fn(3, 1, |x, _| Rgb([[0, 128, 255][x as usize]; 3])));
+        let classificador = ClassificadorOcupacao { desconhecido: Some((100, 150)), ..Default::default() };
+        assert_eq!(
+            classificador.classificar_estados(&img),
+            vec![EstadoCelula::Ocupado, EstadoCelula::Desconhecido, EstadoCelula::Livre]
+        );
+    }
+}
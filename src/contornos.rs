@@ -0,0 +1,386 @@
+
+//! #  Extração dos obstáculos do mapa como polígonos
+//! Segue a borda de cada obstáculo (pixels em que `OccupancyMap::pixel_ocupado` é true) com o traçado de Moore,
+//! incluindo os buracos (regiões livres cercadas pelo obstáculo), e simplifica os contornos com Douglas–Peucker.
+//! ## Output esperado
+//! Uma lista de `PoligonoComBuracos` em coordenadas de pixel, que pode ser salva em CSV com `salvar_poligonos_csv`
+//! e usada no grafo de visibilidade (`grafo_visibilidade_com_buracos`).
+//! ### Observações
+//! O contorno externo passa pelo centro dos pixels de borda do obstáculo e o contorno de um buraco passa pelo
+//! centro dos pixels livres de borda. O contorno externo tem área com sinal positiva e os buracos, negativa.
+
+#![allow(warnings)]
+
+use crate::readmap::OccupancyMap;
+use crate::structs::{Point, Poligono, PoligonoComBuracos};
+use csv::Writer;
+use std::collections::VecDeque;
+use std::error::Error;
+
+/// Vizinhança de 8 em sentido horário na imagem (y para baixo), começando no leste
+const DIRECOES: [(i64, i64); 8] = [(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)];
+/// Índice do oeste em `DIRECOES`
+const OESTE: usize = 4;
+
+/// Índice em `DIRECOES` do deslocamento (dx, dy)
+fn direcao(dx: i64, dy: i64) -> usize {
+    DIRECOES.iter().position(|&d| d == (dx, dy)).expect("Deslocamento entre pixels vizinhos.")
+}
+
+/// Rótulo de "sem componente"
+const SEM_ROTULO: u32 = u32::MAX;
+
+/// Rotula as componentes conexas dos pixels em que `incluir` é true.
+/// Usa vizinhança de 8 se `oito_vizinhos`, senão de 4. Retorna os rótulos e o primeiro pixel (na varredura) de cada componente.
+fn rotular(largura: usize, altura: usize, incluir: impl Fn(usize, usize) -> bool, oito_vizinhos: bool) -> (Vec<u32>, Vec<(usize, usize)>) {
+    let mut rotulos = vec![SEM_ROTULO; largura * altura];
+    let mut primeiros = Vec::new();
+    let vizinhos: &[(i64, i64)] = if oito_vizinhos { &DIRECOES } else { &[(1, 0), (0, 1), (-1, 0), (0, -1)] };
+
+    for y in 0..altura {
+        for x in 0..largura {
+            if rotulos[y * largura + x] != SEM_ROTULO || !incluir(x, y) {
+                continue;
+            }
+            let rotulo = primeiros.len() as u32;
+            primeiros.push((x, y));
+            rotulos[y * largura + x] = rotulo;
+
+            // Busca em largura pela componente
+            let mut fila = VecDeque::from([(x, y)]);
+            while let Some((cx, cy)) = fila.pop_front() {
+                for &(dx, dy) in vizinhos {
+                    let (nx, ny) = (cx as i64 + dx, cy as i64 + dy);
+                    if nx < 0 || ny < 0 || nx >= largura as i64 || ny >= altura as i64 {
+                        continue;
+                    }
+                    let (nx, ny) = (nx as usize, ny as usize);
+                    if rotulos[ny * largura + nx] == SEM_ROTULO && incluir(nx, ny) {
+                        rotulos[ny * largura + nx] = rotulo;
+                        fila.push_back((nx, ny));
+                    }
+                }
+            }
+        }
+    }
+    (rotulos, primeiros)
+}
+
+/// Traçado de Moore: segue a borda da região em que `dentro` é true a partir de `inicio`,
+/// que precisa ser o primeiro pixel da região na varredura (linha por linha, da esquerda para a direita).
+/// Para quando volta ao início andando na mesma direção do primeiro passo (critério de Jacob).
+fn tracar_borda(inicio: (i64, i64), dentro: impl Fn(i64, i64) -> bool) -> Vec<(i64, i64)> {
+    let mut contorno = vec![inicio];
+    let mut atual = inicio;
+    // Direção (a partir do pixel atual) do último vizinho fora da região; no início é o oeste
+    let mut volta = OESTE;
+    let mut primeiro_passo: Option<usize> = None;
+
+    loop {
+        // Gira no sentido horário a partir do vizinho de trás até achar um pixel da região
+        let achado = (1..=8).map(|i| (volta + i) % 8).find_map(|d| {
+            let prox = (atual.0 + DIRECOES[d].0, atual.1 + DIRECOES[d].1);
+            if dentro(prox.0, prox.1) { Some((d, prox)) } else { None }
+        });
+        let (d, prox) = match achado {
+            Some(a) => a,
+            None => break, // pixel isolado
+        };
+
+        if atual == inicio {
+            match primeiro_passo {
+                None => primeiro_passo = Some(d),
+                Some(p) if p == d => break,
+                _ => {}
+            }
+        }
+
+        // O vizinho checado logo antes de `prox` está fora da região e vira o novo "de trás"
+        let tras = (atual.0 + DIRECOES[(d + 7) % 8].0, atual.1 + DIRECOES[(d + 7) % 8].1);
+        volta = direcao(tras.0 - prox.0, tras.1 - prox.1);
+        contorno.push(prox);
+        atual = prox;
+    }
+
+    // A volta ao início fica registrada no final; o anel já fecha sozinho
+    if contorno.len() > 1 && contorno.last() == contorno.first() {
+        contorno.pop();
+    }
+    contorno
+}
+
+/// Converte um contorno de pixels em polígono, no sentido pedido (área positiva ou negativa)
+fn para_poligono(contorno: &[(i64, i64)], area_positiva: bool) -> Poligono {
+    let mut poligono = Poligono { vertices: contorno.iter().map(|&(x, y)| Point { x: x as f64, y: y as f64 }).collect() };
+    if (poligono.area_com_sinal() > 0.0) != area_positiva {
+        poligono.vertices.reverse();
+    }
+    poligono
+}
+
+/// Extrai os contornos de todos os obstáculos do mapa, sem simplificação (um vértice por pixel de borda).
+/// Obstáculos usam vizinhança de 8 e regiões livres vizinhança de 4, para os dois nunca se cruzarem.
+pub fn extrair_contornos(map: &OccupancyMap) -> Vec<PoligonoComBuracos> {
+    let (largura, altura) = map.image.dimensions();
+    let (largura, altura) = (largura as usize, altura as usize);
+    let ocupado = |x: usize, y: usize| map.pixel_ocupado(x as u32, y as u32);
+
+    let (rotulos_obst, primeiros_obst) = rotular(largura, altura, ocupado, true);
+    let (rotulos_livres, primeiros_livres) = rotular(largura, altura, |x, y| !ocupado(x, y), false);
+
+    // Contorno externo de cada obstáculo
+    let mut poligonos: Vec<PoligonoComBuracos> = primeiros_obst
+        .iter()
+        .enumerate()
+        .map(|(rotulo, &(x, y))| {
+            let contorno = tracar_borda((x as i64, y as i64), |cx, cy| {
+                cx >= 0 && cy >= 0 && (cx as usize) < largura && (cy as usize) < altura
+                    && rotulos_obst[cy as usize * largura + cx as usize] == rotulo as u32
+            });
+            PoligonoComBuracos { externo: para_poligono(&contorno, true), buracos: Vec::new() }
+        })
+        .collect();
+
+    // Regiões livres que não encostam na borda da imagem são buracos de algum obstáculo
+    let mut toca_borda = vec![false; primeiros_livres.len()];
+    for y in 0..altura {
+        for x in 0..largura {
+            if x == 0 || y == 0 || x == largura - 1 || y == altura - 1 {
+                let r = rotulos_livres[y * largura + x];
+                if r != SEM_ROTULO {
+                    toca_borda[r as usize] = true;
+                }
+            }
+        }
+    }
+
+    for (rotulo, &(x, y)) in primeiros_livres.iter().enumerate() {
+        if toca_borda[rotulo] {
+            continue;
+        }
+        // O pixel logo acima do primeiro pixel do buraco é do obstáculo que cerca o buraco
+        let dono = rotulos_obst[(y - 1) * largura + x] as usize;
+        let contorno = tracar_borda((x as i64, y as i64), |cx, cy| {
+            cx >= 0 && cy >= 0 && (cx as usize) < largura && (cy as usize) < altura
+                && rotulos_livres[cy as usize * largura + cx as usize] == rotulo as u32
+        });
+        poligonos[dono].buracos.push(para_poligono(&contorno, false));
+    }
+
+    poligonos
+}
+
+/// Distância de `p` ao segmento `ab`
+fn dist_segmento(p: &Point, a: &Point, b: &Point) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let comprimento2 = dx * dx + dy * dy;
+    if comprimento2 == 0.0 {
+        return p.dist(a);
+    }
+    let t = (((p.x - a.x) * dx + (p.y - a.y) * dy) / comprimento2).clamp(0.0, 1.0);
+    p.dist(&Point { x: a.x + t * dx, y: a.y + t * dy })
+}
+
+/// Douglas–Peucker em uma cadeia aberta: marca em `manter` os pontos que ficam
+fn simplificar_cadeia(pontos: &[Point], epsilon: f64, manter: &mut [bool]) {
+    // Pilha de intervalos (início, fim) em vez de recursão, pois contornos podem ter milhares de pontos
+    let mut pilha = vec![(0, pontos.len() - 1)];
+    while let Some((inicio, fim)) = pilha.pop() {
+        manter[inicio] = true;
+        manter[fim] = true;
+        if fim <= inicio + 1 {
+            continue;
+        }
+
+        let (mut maior, mut idx) = (0.0, inicio);
+        for i in (inicio + 1)..fim {
+            let d = dist_segmento(&pontos[i], &pontos[inicio], &pontos[fim]);
+            if d > maior {
+                maior = d;
+                idx = i;
+            }
+        }
+        if maior > epsilon {
+            pilha.push((inicio, idx));
+            pilha.push((idx, fim));
+        }
+    }
+}
+
+/// Simplifica um polígono fechado com Douglas–Peucker: nenhum ponto removido fica a mais de `epsilon` pixels
+/// do contorno simplificado. O anel é dividido no primeiro vértice e no vértice mais distante dele; no fim
+/// o primeiro vértice também sai se for redundante (no meio de um lado reto, por exemplo).
+pub fn douglas_peucker(poligono: &Poligono, epsilon: f64) -> Poligono {
+    let v = &poligono.vertices;
+    if v.len() < 4 {
+        return poligono.clone();
+    }
+
+    let oposto = (1..v.len()).max_by(|&i, &j| v[0].dist(&v[i]).total_cmp(&v[0].dist(&v[j]))).unwrap();
+    // Duas cadeias: 0..=oposto e oposto..=n (com n sendo o próprio vértice 0 de novo)
+    let mut fechado = v.clone();
+    fechado.push(v[0]);
+    let mut manter = vec![false; fechado.len()];
+    simplificar_cadeia(&fechado[..=oposto], epsilon, &mut manter[..=oposto]);
+    simplificar_cadeia(&fechado[oposto..], epsilon, &mut manter[oposto..]);
+
+    let mut mantidos: Vec<usize> = (0..v.len()).filter(|&i| manter[i]).collect();
+    // O vértice 0 é ponta das duas cadeias, então sempre sobra. Sai se todos os pontos entre o mantido
+    // anterior e o seguinte (passando por ele) ficam a até `epsilon` do lado que liga esses dois.
+    if mantidos.len() > 3 {
+        let (anterior, seguinte) = (*mantidos.last().unwrap(), mantidos[1]);
+        let redundante = (anterior + 1..v.len())
+            .chain(0..seguinte)
+            .all(|i| dist_segmento(&v[i], &v[anterior], &v[seguinte]) <= epsilon);
+        if redundante {
+            mantidos.remove(0);
+        }
+    }
+
+    Poligono { vertices: mantidos.into_iter().map(|i| v[i]).collect() }
+}
+
+/// Extrai os obstáculos do mapa como polígonos simplificados com tolerância `epsilon` (em pixels).
+/// Anéis com menos de 3 vértices depois da simplificação (ruído de poucos pixels) são descartados.
+pub fn extrair_poligonos(map: &OccupancyMap, epsilon: f64) -> Vec<PoligonoComBuracos> {
+    extrair_contornos(map)
+        .into_iter()
+        .filter_map(|p| {
+            let externo = douglas_peucker(&p.externo, epsilon);
+            if externo.vertices.len() < 3 {
+                return None;
+            }
+            let buracos = p.buracos
+                .iter()
+                .map(|b| douglas_peucker(b, epsilon))
+                .filter(|b| b.vertices.len() >= 3)
+                .collect();
+            Some(PoligonoComBuracos { externo, buracos })
+        })
+        .collect()
+}
+
+/// Salva os polígonos em um CSV com cabeçalho 'poligono,anel,vertice,x,y'.
+/// O anel 0 é o contorno externo e os anéis 1, 2, ... são os buracos.
+pub fn salvar_poligonos_csv(poligonos: &[PoligonoComBuracos], file_path: &str) -> Result<(), Box<dyn Error>> {
+    let mut wtr = Writer::from_path(file_path)?;
+    wtr.write_record(&["poligono", "anel", "vertice", "x", "y"])?;
+
+    for (i, p) in poligonos.iter().enumerate() {
+        for (anel, contorno) in std::iter::once(&p.externo).chain(p.buracos.iter()).enumerate() {
+            for (j, v) in contorno.vertices.iter().enumerate() {
+                wtr.write_record(&[i.to_string(), anel.to_string(), j.to_string(), format!("{:.2}", v.x), format!("{:.2}", v.y)])?;
+            }
+        }
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readmap::mapa_de_teste;
+
+    /// Mapa 20x20 com moldura, um bloco 4x4 (x e y de 4 a 7) e um anel 5x5 (x e y de 11 a 15) com um buraco 3x3
+    fn mapa_com_anel() -> OccupancyMap {
+        mapa_de_teste(20, 20, |x, y| {
+            let moldura = x == 0 || y == 0 || x == 19 || y == 19;
+            let bloco = (4..=7).contains(&x) && (4..=7).contains(&y);
+            let anel = (11..=15).contains(&x) && (11..=15).contains(&y) && !((12..=14).contains(&x) && (12..=14).contains(&y));
+            moldura || bloco || anel
+        })
+    }
+
+    fn pontos(coords: &[(f64, f64)]) -> Vec<Point> {
+        coords.iter().map(|&(x, y)| Point { x, y }).collect()
+    }
+
+    /// Distância de `p` ao anel fechado `anel`
+    fn dist_anel(p: &Point, anel: &[Point]) -> f64 {
+        (0..anel.len()).map(|i| dist_segmento(p, &anel[i], &anel[(i + 1) % anel.len()])).fold(f64::INFINITY, f64::min)
+    }
+
+    #[test]
+    fn tracar_borda_segue_o_sentido_horario() {
+        // Retângulo 3x2 com o canto em (0, 0)
+        let contorno = tracar_borda((0, 0), |x, y| (0..3).contains(&x) && (0..2).contains(&y));
+        assert_eq!(contorno, vec![(0, 0), (1, 0), (2, 0), (2, 1), (1, 1), (0, 1)]);
+        // Pixel isolado
+        assert_eq!(tracar_borda((5, 5), |x, y| (x, y) == (5, 5)), vec![(5, 5)]);
+    }
+
+    #[test]
+    fn buracos_ficam_com_o_obstaculo_que_os_cerca() {
+        let poligonos = extrair_contornos(&mapa_com_anel());
+        assert_eq!(poligonos.len(), 3);
+        let (moldura, bloco, anel) = (&poligonos[0], &poligonos[1], &poligonos[2]);
+
+        assert_eq!(moldura.externo.vertices[0], Point { x: 0.0, y: 0.0 });
+        assert_eq!(moldura.buracos.len(), 1);
+        assert_eq!(bloco.buracos.len(), 0);
+        assert_eq!(anel.buracos.len(), 1);
+
+        // O buraco do anel passa pelo centro dos pixels livres de borda (todos os 8 em volta do centro)
+        assert_eq!(anel.buracos[0].vertices.len(), 8);
+        assert!(anel.buracos[0].vertices.iter().all(|p| (12.0..=14.0).contains(&p.x) && (12.0..=14.0).contains(&p.y)));
+        assert!(anel.contem(&Point { x: 11.5, y: 13.0 }) && !anel.contem(&Point { x: 13.0, y: 13.0 }));
+    }
+
+    #[test]
+    fn externo_positivo_e_buracos_negativos() {
+        for p in extrair_contornos(&mapa_com_anel()) {
+            assert!(p.externo.area_com_sinal() > 0.0);
+            assert!(p.buracos.iter().all(|b| b.area_com_sinal() < 0.0));
+        }
+        // O bloco 4x4 tem contorno pelos centros dos pixels de borda: quadrado de lado 3
+        let bloco = &extrair_contornos(&mapa_com_anel())[1];
+        assert_eq!(bloco.externo.area_com_sinal(), 9.0);
+    }
+
+    #[test]
+    fn douglas_peucker_respeita_o_epsilon() {
+        let bloco = extrair_contornos(&mapa_com_anel())[1].externo.clone();
+        assert_eq!(bloco.vertices.len(), 12);
+
+        // Só os quatro cantos sobram
+        let simplificado = douglas_peucker(&bloco, 0.5);
+        assert_eq!(simplificado.vertices, pontos(&[(4.0, 4.0), (7.0, 4.0), (7.0, 7.0), (4.0, 7.0)]));
+
+        // Um triângulo raso some com epsilon maior que a altura e fica com epsilon menor
+        let raso = Poligono { vertices: pontos(&[(0.0, 0.0), (5.0, 0.8), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]) };
+        assert_eq!(douglas_peucker(&raso, 1.0).vertices.len(), 4);
+        assert_eq!(douglas_peucker(&raso, 0.5).vertices.len(), 5);
+
+        // O vértice 0 no meio de um lado também sai (o buraco da moldura começa em (1, 2))
+        let buraco = Poligono { vertices: pontos(&[(1.0, 2.0), (1.0, 18.0), (18.0, 18.0), (18.0, 1.0), (1.0, 1.0)]) };
+        assert_eq!(douglas_peucker(&buraco, 0.5).vertices, pontos(&[(1.0, 18.0), (18.0, 18.0), (18.0, 1.0), (1.0, 1.0)]));
+        let moldura = &extrair_contornos(&mapa_com_anel())[0];
+        assert!(moldura.buracos.iter().all(|b| douglas_peucker(b, 0.5).vertices.len() == 4));
+
+        // Nenhum vértice removido fica a mais de epsilon do contorno simplificado
+        for p in extrair_contornos(&mapa_com_anel()) {
+            for anel in std::iter::once(&p.externo).chain(p.buracos.iter()) {
+                for epsilon in [0.5, 1.5, 3.0] {
+                    let simplificado = douglas_peucker(anel, epsilon);
+                    assert!(anel.vertices.iter().all(|v| dist_anel(v, &simplificado.vertices) <= epsilon + 1e-9));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn salva_um_vertice_por_linha() {
+        let poligonos = extrair_poligonos(&mapa_com_anel(), 0.5);
+        let caminho = std::env::temp_dir().join(format!("prm_contornos_csv_{}.csv", std::process::id()));
+        salvar_poligonos_csv(&poligonos, caminho.to_str().unwrap()).unwrap();
+
+        let conteudo = std::fs::read_to_string(&caminho).unwrap();
+        let linhas: Vec<&str> = conteudo.lines().collect();
+        assert_eq!(linhas[0], "poligono,anel,vertice,x,y");
+        let total: usize = poligonos.iter().map(|p| p.externo.vertices.len() + p.buracos.iter().map(|b| b.vertices.len()).sum::<usize>()).sum();
+        assert_eq!(linhas.len(), 1 + total);
+        assert!(linhas.iter().any(|l| l.starts_with("2,1,")), "falta o buraco do anel");
+    }
+}
@@ -2,7 +2,7 @@
 //! #  Calcula o caminho entre dois vértices.
 
 #![allow(warnings)]
-use crate::structs::{Graph};
+use crate::structs::{Graph, Point};
 use std::fs::File;
 use std::io::Write;
 use csv::Writer;
@@ -69,5 +69,24 @@ pub fn path_export(gr: &Graph, path: &[usize], filename: &str) -> Result<(), Box
         wtr.write_record(&[point.x.to_string(), point.y.to_string()])?;
     }
 
+    wtr.flush()?;
     Ok(())
 }
+
+/// Exporta um caminho dado direto como pontos (planejadores em grade, RRT etc.) no mesmo formato do `path_export`
+pub fn path_export_pontos(path: &[Point], filename: &str) -> Result<(), Box<dyn std::error::Error>> {
+
+    let mut wtr = Writer::from_path(filename)?;
+    wtr.write_record(&["x", "y"])?;
+    for point in path {
+        wtr.write_record(&[point.x.to_string(), point.y.to_string()])?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+/// Retorna o comprimento (em pixels) de um caminho, somando a distância entre vértices consecutivos
+pub fn comprimento_caminho(gr: &Graph, path: &[usize]) -> f64 {
+    path.windows(2).map(|par| gr.vertices[par[0]].dist(&gr.vertices[par[1]])).sum()
+}
@@ -0,0 +1,126 @@
+
+//! #  Calcula o caminho mínimo entre dois vértices em um grafo qualquer.
+//! Diferente do `dfs_path`, que só acha o caminho certo em árvores, o Dijkstra acha o caminho
+//! de menor peso total em grafos com ciclos (o PRM completo, o grafo de visibilidade etc).
+
+#![allow(warnings)]
+use crate::structs::{Graph};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Item da heap: custo acumulado até um vértice
+#[derive(Debug, Clone, Copy)]
+struct Estado {
+    custo: f64,
+    vertice: usize,
+}
+
+impl PartialEq for Estado {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Estado {}
+
+impl Ord for Estado {
+    /// Ordem invertida (BinaryHeap é de máximo), então o menor custo sai primeiro
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.custo.total_cmp(&self.custo)
+            .then_with(|| other.vertice.cmp(&self.vertice))
+    }
+}
+
+impl PartialOrd for Estado {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Encontra o caminho de menor peso entre dois vértices usando o algoritmo de Dijkstra.
+/// O caminho é uma lista de indices do vértice, no mesmo formato do `dfs_path`.
+pub fn dijkstra_path(gr: &Graph, start: usize, end: usize) -> Option<Vec<usize>> {
+    let n = gr.vertices.len();
+    if start >= n || end >= n {
+        return None;
+    }
+
+    // Menor custo conhecido até cada vértice e de onde ele veio
+    let mut dist = vec![f64::INFINITY; n];
+    let mut anterior: Vec<Option<usize>> = vec![None; n];
+    let mut heap = BinaryHeap::new();
+
+    dist[start] = 0.0;
+    heap.push(Estado { custo: 0.0, vertice: start });
+
+    while let Some(Estado { custo, vertice }) = heap.pop() {
+        // Chegou no destino com o menor custo possível
+        if vertice == end {
+            break;
+        }
+        // Entrada velha da heap (já achamos um caminho melhor para esse vértice)
+        if custo > dist[vertice] {
+            continue;
+        }
+
+        // Relaxa as arestas do vértice atual
+        for edge in &gr.adj[vertice] {
+            let novo_custo = custo + edge.weight.valor();
+            if novo_custo < dist[edge.to_idx] {
+                dist[edge.to_idx] = novo_custo;
+                anterior[edge.to_idx] = Some(vertice);
+                heap.push(Estado { custo: novo_custo, vertice: edge.to_idx });
+            }
+        }
+    }
+
+    if dist[end].is_infinite() {
+        return None;
+    }
+
+    // Reconstrói o caminho voltando do destino até a origem
+    let mut path = vec![end];
+    let mut atual = end;
+    while let Some(prev) = anterior[atual] {
+        path.push(prev);
+        atual = prev;
+    }
+    path.reverse();
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::Point;
+
+    /// 0 -> 3 direto custa 9; 0-1-3 começa barato mas custa 11; o ótimo é 0-2-4-3 com custo 7
+    fn grafo_com_armadilha() -> Graph {
+        let mut gr = Graph::new();
+        for i in 0..6 {
+            gr.add_vertex(Point { x: i as f64, y: 0.0 });
+        }
+        for (u, v, w) in [(0, 1, 1.0), (1, 3, 10.0), (0, 2, 3.0), (2, 3, 5.0), (2, 4, 2.0), (4, 3, 2.0), (0, 3, 9.0)] {
+            gr.add_edge(u, v, w).unwrap();
+        }
+        gr
+    }
+
+    #[test]
+    fn acha_o_caminho_de_menor_peso() {
+        let gr = grafo_com_armadilha();
+        assert_eq!(dijkstra_path(&gr, 0, 3), Some(vec![0, 2, 4, 3]));
+        assert_eq!(dijkstra_path(&gr, 3, 0), Some(vec![3, 4, 2, 0]));
+        assert_eq!(dijkstra_path(&gr, 1, 4), Some(vec![1, 0, 2, 4]));
+        assert_eq!(dijkstra_path(&gr, 2, 2), Some(vec![2]));
+    }
+
+    #[test]
+    fn sem_caminho_ou_indice_invalido() {
+        let gr = grafo_com_armadilha();
+        // O vértice 5 não tem arestas
+        assert_eq!(dijkstra_path(&gr, 0, 5), None);
+        assert_eq!(dijkstra_path(&gr, 0, 6), None);
+        assert_eq!(dijkstra_path(&gr, 6, 0), None);
+    }
+}
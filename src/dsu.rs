@@ -0,0 +1,221 @@
+
+//! #  Disjoint Set Union (conjuntos disjuntos)
+//! Estrutura usada para detectar ciclos e acompanhar componentes conexas. Usa união por tamanho e `find`
+//! iterativo, guarda o tamanho de cada conjunto e a quantidade de componentes, permite listar os membros
+//! e, quando criada com `DSU::com_rollback`, desfazer uniões (útil em algoritmos offline).
+
+#![allow(warnings)]
+
+/// Disjoint Set Union, usada para detectar ciclos no grafo/árvore e acompanhar componentes
+#[derive(Debug, Clone)]
+pub struct DSU {
+    /// Aponta quem é o "pai" do elemento i
+    parent: Vec<usize>,
+    /// Tamanho do conjunto de cada raíz (só é válido para as raízes)
+    size: Vec<usize>,
+    /// Quantidade de conjuntos disjuntos
+    componentes: usize,
+    /// Se `Some`, guarda cada união feita como (raíz absorvida, raíz que absorveu) para poder desfazer
+    historico: Option<Vec<(usize, usize)>>,
+}
+
+impl DSU {
+
+    /// Retorna um DSU novo, com cada elemento em seu próprio conjunto
+    pub fn new(size: usize) -> Self {
+        /// Cria uma lista com [0, 1, ..., size-1], ou seja, cada vértice é seu proprio pai em conjuntos separados
+        DSU {
+            parent: (0..size).collect(),
+            size: vec![1; size],
+            componentes: size,
+            historico: None,
+        }
+    }
+
+    /// Retorna um DSU novo que permite desfazer uniões com `rollback`.
+    /// Nesse modo o `find` não faz compressão de caminho (ela impediria desfazer as uniões),
+    /// então o custo fica O(log n) por operação graças à união por tamanho.
+    pub fn com_rollback(size: usize) -> Self {
+        let mut dsu = DSU::new(size);
+        dsu.historico = Some(Vec::new());
+        dsu
+    }
+
+    /// Quantidade de elementos
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Retorna true se não há elementos
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Retorna a raíz do conjunto de `x` sem alterar a estrutura
+    pub fn raiz(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Retorna a raíz do conjunto de `x`
+    pub fn find(&mut self, x: usize) -> usize {
+        /// Sobe até a raíz de forma iterativa (sem risco de estourar a pilha em caminhos longos)
+        let raiz = self.raiz(x);
+
+        /// Atualiza o pai de todo o caminho já para a raíz para não ter buscas desnecessárias no futuro
+        if self.historico.is_none() {
+            let mut atual = x;
+            while self.parent[atual] != raiz {
+                let proximo = self.parent[atual];
+                self.parent[atual] = raiz;
+                atual = proximo;
+            }
+        }
+        raiz
+    }
+
+    /// Une dois conjuntos `a` e `b` se não estiverem conectados
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        /// Pega as raízes de cada conjunto
+        let mut rootA = self.find(a);
+        let mut rootB = self.find(b);
+
+        /// Se as raízes forem iguais, `a` e `b` já estão no mesmo conjunto e portanto a união formaria um ciclo
+        if rootA == rootB {
+            return false;
+        }
+
+        /// O conjunto menor é pendurado no maior para as árvores continuarem rasas.
+        /// Em empate a raíz de `a` continua sendo a raíz.
+        if self.size[rootA] < self.size[rootB] {
+            std::mem::swap(&mut rootA, &mut rootB);
+        }
+        self.parent[rootB] = rootA;
+        self.size[rootA] += self.size[rootB];
+        self.componentes -= 1;
+
+        if let Some(historico) = &mut self.historico {
+            historico.push((rootB, rootA));
+        }
+        true
+    }
+
+    /// Retorna true se `a` e `b` estão no mesmo conjunto
+    pub fn conectados(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Quantidade de conjuntos disjuntos (componentes)
+    pub fn num_componentes(&self) -> usize {
+        self.componentes
+    }
+
+    /// Tamanho do conjunto que contém `x`
+    pub fn tamanho(&mut self, x: usize) -> usize {
+        let raiz = self.find(x);
+        self.size[raiz]
+    }
+
+    /// Lista os membros do conjunto que contém `x`, em ordem crescente
+    pub fn membros(&mut self, x: usize) -> Vec<usize> {
+        let raiz = self.find(x);
+        (0..self.len()).filter(|&i| self.find(i) == raiz).collect()
+    }
+
+    /// Lista todos os conjuntos, cada um em ordem crescente e ordenados pelo menor elemento
+    pub fn conjuntos(&mut self) -> Vec<Vec<usize>> {
+        let mut indice_do_conjunto = vec![usize::MAX; self.len()];
+        let mut conjuntos: Vec<Vec<usize>> = Vec::with_capacity(self.componentes);
+
+        for i in 0..self.len() {
+            let raiz = self.find(i);
+            if indice_do_conjunto[raiz] == usize::MAX {
+                indice_do_conjunto[raiz] = conjuntos.len();
+                conjuntos.push(Vec::new());
+            }
+            conjuntos[indice_do_conjunto[raiz]].push(i);
+        }
+        conjuntos
+    }
+
+    /// Retorna um marcador do estado atual para usar com `rollback`.
+    /// Só faz sentido em um DSU criado com `com_rollback`.
+    pub fn checkpoint(&self) -> usize {
+        self.historico.as_ref().map_or(0, |h| h.len())
+    }
+
+    /// Desfaz todas as uniões feitas depois do `checkpoint` informado
+    pub fn rollback(&mut self, checkpoint: usize) -> Result<(), String> {
+        let historico = match &mut self.historico {
+            Some(h) => h,
+            None => return Err("Este DSU não foi criado com suporte a rollback.".to_string()),
+        };
+        if checkpoint > historico.len() {
+            return Err(format!("Checkpoint {} inválido, o histórico tem {} uniões.", checkpoint, historico.len()));
+        }
+
+        /// Desfaz as uniões da mais recente para a mais antiga
+        while historico.len() > checkpoint {
+            let (absorvida, raiz) = historico.pop().unwrap();
+            self.parent[absorvida] = absorvida;
+            self.size[raiz] -= self.size[absorvida];
+            self.componentes += 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniao_tamanho_e_componentes() {
+        let mut dsu = DSU::new(6);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(2, 3));
+        assert!(dsu.union(1, 3));
+        assert!(!dsu.union(0, 2), "0 e 2 já estavam no mesmo conjunto");
+        assert_eq!(dsu.num_componentes(), 3);
+        assert_eq!(dsu.tamanho(3), 4);
+        assert_eq!(dsu.membros(2), vec![0, 1, 2, 3]);
+        assert_eq!(dsu.conjuntos(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+    }
+
+    #[test]
+    fn rollback_desfaz_as_unioes() {
+        let mut dsu = DSU::com_rollback(6);
+        dsu.union(0, 1);
+        dsu.union(2, 3);
+        let checkpoint = dsu.checkpoint();
+        let antes = dsu.conjuntos();
+
+        dsu.union(1, 3);
+        dsu.union(4, 5);
+        dsu.union(5, 0);
+        assert_eq!(dsu.num_componentes(), 1);
+
+        dsu.rollback(checkpoint).unwrap();
+        assert_eq!(dsu.conjuntos(), antes);
+        assert_eq!(dsu.num_componentes(), 4);
+        assert_eq!(dsu.tamanho(0), 2);
+        assert!(!dsu.conectados(1, 3));
+
+        // Depois do rollback a estrutura continua usável
+        assert!(dsu.union(1, 3));
+        dsu.rollback(0).unwrap();
+        assert_eq!(dsu.num_componentes(), 6);
+    }
+
+    #[test]
+    fn rollback_invalido() {
+        let mut sem_historico = DSU::new(3);
+        assert!(sem_historico.rollback(0).is_err());
+
+        let mut dsu = DSU::com_rollback(3);
+        dsu.union(0, 1);
+        assert!(dsu.rollback(2).is_err());
+    }
+}
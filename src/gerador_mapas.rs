@@ -0,0 +1,315 @@
+
+//! #  Gerador procedural de mapas para testes
+//! Gera mapas de ocupação reproduzíveis (mesma semente = mesmo mapa) para não depender só do `data/map.jpg`:
+//! - `TipoMapa::Aleatorio`: retângulos e círculos espalhados (não garante que o espaço livre seja conexo)
+//! - `TipoMapa::Labirinto`: labirinto perfeito (busca em profundidade), com corredores e paredes de espessura fixa
+//! - `TipoMapa::Salas`: salas separadas por paredes com portas (divisão recursiva)
+//! - `TipoMapa::Corredores`: paredes atravessando o mapa, cada uma com uma passagem estreita
+//!
+//! Todos os mapas têm uma moldura de obstáculo de 1 pixel, como o mapa original.
+//! ## Output esperado
+//! Um `MapaGerado`, que vira um `OccupancyMap` em memória ou é salvo como imagem.
+
+#![allow(warnings)]
+
+use crate::classificador::EstadoCelula;
+use crate::readmap::{ImageBuffer, OccupancyMap, Rgb};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Tipo de mapa e seus parâmetros (tamanhos em pixels)
+#[derive(Debug, Clone, PartialEq)]
+pub enum TipoMapa {
+    Aleatorio { largura: u32, altura: u32, retangulos: usize, circulos: usize, tamanho_min: u32, tamanho_max: u32 },
+    /// O tamanho final é `colunas * (corredor + parede) + parede` por `linhas * (corredor + parede) + parede`
+    Labirinto { colunas: u32, linhas: u32, corredor: u32, parede: u32 },
+    /// Salas com pelo menos `sala_min` pixels de lado e portas de largura `porta`
+    Salas { largura: u32, altura: u32, sala_min: u32, porta: u32 },
+    /// `paredes` paredes verticais igualmente espaçadas, cada uma com uma passagem de altura `corredor`
+    Corredores { largura: u32, altura: u32, paredes: usize, corredor: u32 },
+}
+
+/// Mapa gerado: o estado de cada pixel, indexado por `y * largura + x`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapaGerado {
+    pub largura: u32,
+    pub altura: u32,
+    pub estados: Vec<EstadoCelula>,
+}
+
+impl MapaGerado {
+    /// Mapa todo livre (ou todo ocupado) com a moldura de obstáculo
+    fn novo(largura: u32, altura: u32, estado: EstadoCelula) -> Self {
+        let mut mapa = MapaGerado { largura, altura, estados: vec![estado; (largura * altura) as usize] };
+        mapa.retangulo(0, 0, largura, 1, EstadoCelula::Ocupado);
+        mapa.retangulo(0, altura - 1, largura, 1, EstadoCelula::Ocupado);
+        mapa.retangulo(0, 0, 1, altura, EstadoCelula::Ocupado);
+        mapa.retangulo(largura - 1, 0, 1, altura, EstadoCelula::Ocupado);
+        mapa
+    }
+
+    fn set(&mut self, x: i64, y: i64, estado: EstadoCelula) {
+        if x >= 0 && y >= 0 && (x as u32) < self.largura && (y as u32) < self.altura {
+            self.estados[(y as u32 * self.largura + x as u32) as usize] = estado;
+        }
+    }
+
+    fn get(&self, x: u32, y: u32) -> EstadoCelula {
+        self.estados[(y * self.largura + x) as usize]
+    }
+
+    /// Pinta o retângulo [x, x + w) x [y, y + h), cortado nos limites do mapa
+    fn retangulo(&mut self, x: u32, y: u32, w: u32, h: u32, estado: EstadoCelula) {
+        for yy in y..(y + h).min(self.altura) {
+            for xx in x..(x + w).min(self.largura) {
+                self.set(xx as i64, yy as i64, estado);
+            }
+        }
+    }
+
+    fn circulo(&mut self, cx: f64, cy: f64, raio: f64, estado: EstadoCelula) {
+        for y in (cy - raio).floor() as i64..=(cy + raio).ceil() as i64 {
+            for x in (cx - raio).floor() as i64..=(cx + raio).ceil() as i64 {
+                if (x as f64 - cx).powi(2) + (y as f64 - cy).powi(2) <= raio * raio {
+                    self.set(x, y, estado);
+                }
+            }
+        }
+    }
+
+    /// Imagem do mapa: livre = branco, obstáculo = preto, desconhecido = cinza 205
+    pub fn imagem(&self) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+        ImageBuffer::from_fn(self.largura, self.altura, |x, y| match self.get(x, y) {
+            EstadoCelula::Livre => Rgb([255, 255, 255]),
+            EstadoCelula::Ocupado => Rgb([0, 0, 0]),
+            EstadoCelula::Desconhecido => Rgb([205, 205, 205]),
+        })
+    }
+
+    /// Salva a imagem (o formato vem da extensão, ex.: `.png`)
+    pub fn salvar_imagem(&self, path: &str) -> Result<(), String> {
+        self.imagem().save(path).map_err(|e| format!("Não foi possível salvar o mapa '{}': {}", path, e))
+    }
+
+    /// Converte para um `OccupancyMap` em memória
+    pub fn para_occupancy_map(&self) -> OccupancyMap {
+        OccupancyMap::de_estados(self.largura, self.altura, self.estados.clone())
+            .expect("O gerador sempre produz um mapa não vazio com uma célula por pixel.")
+    }
+}
+
+/// Gera o mapa do tipo pedido com a semente dada
+pub fn gerar_mapa(tipo: &TipoMapa, seed: u64) -> Result<MapaGerado, String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    match *tipo {
+        TipoMapa::Aleatorio { largura, altura, retangulos, circulos, tamanho_min, tamanho_max } => {
+            if largura < 3 || altura < 3 {
+                return Err("O mapa precisa ter pelo menos 3x3 pixels.".to_string());
+            }
+            if tamanho_min == 0 || tamanho_min > tamanho_max {
+                return Err(format!("Tamanhos inválidos: mínimo {}, máximo {}.", tamanho_min, tamanho_max));
+            }
+            Ok(gerar_aleatorio(&mut rng, largura, altura, retangulos, circulos, tamanho_min, tamanho_max))
+        }
+        TipoMapa::Labirinto { colunas, linhas, corredor, parede } => {
+            if colunas == 0 || linhas == 0 || corredor == 0 || parede == 0 {
+                return Err("O labirinto precisa de colunas, linhas, corredor e parede positivos.".to_string());
+            }
+            Ok(gerar_labirinto(&mut rng, colunas, linhas, corredor, parede))
+        }
+        TipoMapa::Salas { largura, altura, sala_min, porta } => {
+            if sala_min == 0 || porta == 0 || porta > sala_min {
+                return Err(format!("A porta ({}) precisa ser positiva e caber na menor sala ({}).", porta, sala_min));
+            }
+            if largura < sala_min + 2 || altura < sala_min + 2 {
+                return Err(format!("O mapa {}x{} não comporta uma sala de {} pixels.", largura, altura, sala_min));
+            }
+            Ok(gerar_salas(&mut rng, largura, altura, sala_min, porta))
+        }
+        TipoMapa::Corredores { largura, altura, paredes, corredor } => {
+            if corredor == 0 || corredor + 2 > altura {
+                return Err(format!("O corredor ({}) precisa ser positivo e caber na altura ({}).", corredor, altura));
+            }
+            // Cada parede e cada vão entre paredes precisam de pelo menos 1 pixel
+            if (2 * paredes + 3) as u32 > largura {
+                return Err(format!("{} paredes não cabem em {} pixels de largura.", paredes, largura));
+            }
+            Ok(gerar_corredores(&mut rng, largura, altura, paredes, corredor))
+        }
+    }
+}
+
+fn gerar_aleatorio(rng: &mut StdRng, largura: u32, altura: u32, retangulos: usize, circulos: usize, tamanho_min: u32, tamanho_max: u32) -> MapaGerado {
+    let mut mapa = MapaGerado::novo(largura, altura, EstadoCelula::Livre);
+    for _ in 0..retangulos {
+        let (w, h) = (rng.gen_range(tamanho_min..=tamanho_max), rng.gen_range(tamanho_min..=tamanho_max));
+        let (x, y) = (rng.gen_range(0..largura), rng.gen_range(0..altura));
+        mapa.retangulo(x, y, w, h, EstadoCelula::Ocupado);
+    }
+    for _ in 0..circulos {
+        let raio = rng.gen_range(tamanho_min..=tamanho_max) as f64 / 2.0;
+        let (cx, cy) = (rng.gen_range(0.0..largura as f64), rng.gen_range(0.0..altura as f64));
+        mapa.circulo(cx, cy, raio, EstadoCelula::Ocupado);
+    }
+    mapa
+}
+
+fn gerar_labirinto(rng: &mut StdRng, colunas: u32, linhas: u32, corredor: u32, parede: u32) -> MapaGerado {
+    let passo = corredor + parede;
+    let mut mapa = MapaGerado::novo(colunas * passo + parede, linhas * passo + parede, EstadoCelula::Ocupado);
+    // Canto superior esquerdo do espaço livre da célula (c, l)
+    let origem = |c: u32, l: u32| (parede + c * passo, parede + l * passo);
+
+    let mut visitada = vec![false; (colunas * linhas) as usize];
+    let mut pilha = vec![(0u32, 0u32)];
+    visitada[0] = true;
+    let (x, y) = origem(0, 0);
+    mapa.retangulo(x, y, corredor, corredor, EstadoCelula::Livre);
+
+    // Busca em profundidade: escolhe um vizinho não visitado ao acaso e derruba a parede entre os dois
+    while let Some(&(c, l)) = pilha.last() {
+        let vizinhos: Vec<(u32, u32)> = [(0i64, -1i64), (1, 0), (0, 1), (-1, 0)]
+            .iter()
+            .map(|&(dc, dl)| (c as i64 + dc, l as i64 + dl))
+            .filter(|&(nc, nl)| nc >= 0 && nl >= 0 && (nc as u32) < colunas && (nl as u32) < linhas)
+            .map(|(nc, nl)| (nc as u32, nl as u32))
+            .filter(|&(nc, nl)| !visitada[(nl * colunas + nc) as usize])
+            .collect();
+
+        if vizinhos.is_empty() {
+            pilha.pop();
+            continue;
+        }
+        let (nc, nl) = vizinhos[rng.gen_range(0..vizinhos.len())];
+        visitada[(nl * colunas + nc) as usize] = true;
+
+        let (x, y) = origem(nc, nl);
+        mapa.retangulo(x, y, corredor, corredor, EstadoCelula::Livre);
+        // Passagem: o retângulo que cobre as duas células e a parede entre elas
+        let (ax, ay) = origem(c.min(nc), l.min(nl));
+        let (w, h) = if nc != c { (2 * corredor + parede, corredor) } else { (corredor, 2 * corredor + parede) };
+        mapa.retangulo(ax, ay, w, h, EstadoCelula::Livre);
+
+        pilha.push((nc, nl));
+    }
+    mapa
+}
+
+/// Divisão recursiva: cada região grande o bastante ganha uma parede com uma porta, e as duas metades
+/// são divididas de novo. Uma parede nunca encosta numa porta já aberta, então tudo continua conexo.
+fn gerar_salas(rng: &mut StdRng, largura: u32, altura: u32, sala_min: u32, porta: u32) -> MapaGerado {
+    let mut mapa = MapaGerado::novo(largura, altura, EstadoCelula::Livre);
+    // Regiões livres [x0, x1) x [y0, y1) ainda por dividir
+    let mut regioes = vec![(1u32, 1u32, largura - 1, altura - 1)];
+
+    while let Some((x0, y0, x1, y1)) = regioes.pop() {
+        let (w, h) = (x1 - x0, y1 - y0);
+        let pode_vertical = w >= 2 * sala_min + 1;
+        let pode_horizontal = h >= 2 * sala_min + 1;
+        if !pode_vertical && !pode_horizontal {
+            continue;
+        }
+        let vertical = if pode_vertical && pode_horizontal { w >= h } else { pode_vertical };
+
+        // Posições da parede que deixam as duas salas com pelo menos `sala_min` e não tapam portas nas pontas
+        let (inicio, fim) = if vertical { (x0 + sala_min, x1 - sala_min - 1) } else { (y0 + sala_min, y1 - sala_min - 1) };
+        let candidatas: Vec<u32> = (inicio..=fim)
+            .filter(|&p| {
+                let pontas = if vertical { [(p, y0 - 1), (p, y1)] } else { [(x0 - 1, p), (x1, p)] };
+                pontas.iter().all(|&(x, y)| mapa.get(x, y) == EstadoCelula::Ocupado)
+            })
+            .collect();
+        if candidatas.is_empty() {
+            continue;
+        }
+        let p = candidatas[rng.gen_range(0..candidatas.len())];
+
+        if vertical {
+            mapa.retangulo(p, y0, 1, h, EstadoCelula::Ocupado);
+            let porta_y = rng.gen_range(y0..=y1 - porta);
+            mapa.retangulo(p, porta_y, 1, porta, EstadoCelula::Livre);
+            regioes.push((x0, y0, p, y1));
+            regioes.push((p + 1, y0, x1, y1));
+        } else {
+            mapa.retangulo(x0, p, w, 1, EstadoCelula::Ocupado);
+            let porta_x = rng.gen_range(x0..=x1 - porta);
+            mapa.retangulo(porta_x, p, porta, 1, EstadoCelula::Livre);
+            regioes.push((x0, y0, x1, p));
+            regioes.push((x0, p + 1, x1, y1));
+        }
+    }
+    mapa
+}
+
+fn gerar_corredores(rng: &mut StdRng, largura: u32, altura: u32, paredes: usize, corredor: u32) -> MapaGerado {
+    let mut mapa = MapaGerado::novo(largura, altura, EstadoCelula::Livre);
+    let n = paredes as u32;
+    // Espessura das paredes: o mesmo que sobra para cada vão, para as passagens serem de fato corredores
+    let espessura = ((largura - 2) / (2 * n + 1)).max(1);
+    for i in 0..n {
+        let x = 1 + (i + 1) * (largura - 2) / (n + 1) - espessura / 2;
+        mapa.retangulo(x, 1, espessura, altura - 2, EstadoCelula::Ocupado);
+        let y = rng.gen_range(1..=altura - 1 - corredor);
+        mapa.retangulo(x, y, espessura, corredor, EstadoCelula::Livre);
+    }
+    mapa
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LABIRINTO: TipoMapa = TipoMapa::Labirinto { colunas: 8, linhas: 6, corredor: 4, parede: 2 };
+    const SALAS: TipoMapa = TipoMapa::Salas { largura: 120, altura: 90, sala_min: 15, porta: 5 };
+    const CORREDORES: TipoMapa = TipoMapa::Corredores { largura: 100, altura: 60, paredes: 4, corredor: 3 };
+
+    fn tem_moldura(mapa: &MapaGerado) -> bool {
+        let (l, a) = (mapa.largura, mapa.altura);
+        (0..l).all(|x| mapa.get(x, 0) == EstadoCelula::Ocupado && mapa.get(x, a - 1) == EstadoCelula::Ocupado)
+            && (0..a).all(|y| mapa.get(0, y) == EstadoCelula::Ocupado && mapa.get(l - 1, y) == EstadoCelula::Ocupado)
+    }
+
+    #[test]
+    fn mesma_semente_mesmo_mapa() {
+        let aleatorio = TipoMapa::Aleatorio { largura: 80, altura: 60, retangulos: 6, circulos: 4, tamanho_min: 4, tamanho_max: 15 };
+        for tipo in [aleatorio, LABIRINTO, SALAS, CORREDORES] {
+            assert_eq!(gerar_mapa(&tipo, 42).unwrap(), gerar_mapa(&tipo, 42).unwrap());
+            assert_ne!(gerar_mapa(&tipo, 42).unwrap(), gerar_mapa(&tipo, 43).unwrap(), "{:?} ignora a semente", tipo);
+        }
+    }
+
+    #[test]
+    fn labirinto_salas_e_corredores_sao_conexos() {
+        for tipo in [LABIRINTO, SALAS, CORREDORES] {
+            for seed in 0..5 {
+                let mapa = gerar_mapa(&tipo, seed).unwrap();
+                assert!(tem_moldura(&mapa));
+                let regioes = mapa.para_occupancy_map().regioes_livres();
+                assert_eq!(regioes.tamanhos.len(), 1, "{:?} com semente {} não é conexo", tipo, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn tamanho_do_labirinto() {
+        let mapa = gerar_mapa(&LABIRINTO, 0).unwrap();
+        assert_eq!((mapa.largura, mapa.altura), (8 * 6 + 2, 6 * 6 + 2));
+        assert_eq!(mapa.estados.len(), (mapa.largura * mapa.altura) as usize);
+    }
+
+    #[test]
+    fn parametros_invalidos() {
+        let invalidos = [
+            TipoMapa::Aleatorio { largura: 2, altura: 50, retangulos: 1, circulos: 1, tamanho_min: 1, tamanho_max: 2 },
+            TipoMapa::Aleatorio { largura: 50, altura: 50, retangulos: 1, circulos: 1, tamanho_min: 5, tamanho_max: 2 },
+            TipoMapa::Labirinto { colunas: 0, linhas: 4, corredor: 2, parede: 1 },
+            TipoMapa::Salas { largura: 50, altura: 50, sala_min: 10, porta: 11 },
+            TipoMapa::Salas { largura: 10, altura: 50, sala_min: 10, porta: 3 },
+            TipoMapa::Corredores { largura: 50, altura: 5, paredes: 2, corredor: 4 },
+            TipoMapa::Corredores { largura: 8, altura: 50, paredes: 3, corredor: 4 },
+        ];
+        for tipo in invalidos {
+            assert!(gerar_mapa(&tipo, 0).is_err(), "{:?} foi aceito", tipo);
+        }
+    }
+}
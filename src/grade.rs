@@ -0,0 +1,477 @@
+
+//! #  Planejadores em grade sobre os pixels do mapa
+//! Referência determinística (resolução-completa) para comparar com o PRM: cada pixel livre dentro de
+//! `pixel_bounds` é uma célula com 8 vizinhos. Movimentos diagonais só são permitidos se as duas células
+//! ortogonais vizinhas estiverem livres (não corta quina de obstáculo). Células desconhecidas seguem a
+//! `politica_desconhecido` do mapa: com `PermitirComCusto` o custo de cada trecho usa o `custo_pixel`.
+//! - `a_estrela_grade`: A* com heurística octil
+//! - `theta_estrela_grade`: Theta*, qualquer ângulo, com teste de linha de visada na grade
+//! - `jps_grade`: Jump Point Search (mesmo custo do A*, expandindo bem menos células). Pressupõe custo uniforme:
+//!   com células de custo maior o custo devolvido é o do caminho achado, que pode não ser o mais barato.
+//! ## Output esperado
+//! Um `ResultadoGrade` com o caminho em coordenadas de pixel (centros das células), que pode ser salvo com
+//! `path_export_pontos` no mesmo formato do `path_export`.
+
+#![allow(warnings)]
+
+use crate::readmap::OccupancyMap;
+use crate::structs::Point;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+const SQRT_2: f64 = std::f64::consts::SQRT_2;
+
+/// Resultado de um planejador em grade
+#[derive(Debug, Clone)]
+pub struct ResultadoGrade {
+    /// Pontos do caminho, do início ao destino
+    pub caminho: Vec<Point>,
+    /// Custo do caminho (o comprimento em pixels, se nenhuma célula tiver custo maior que 1)
+    pub custo: f64,
+    /// Quantas células foram expandidas (retiradas da heap)
+    pub expandidos: usize,
+}
+
+/// Custo das células dentro dos limites de navegação (infinito = não atravessável)
+struct Grade {
+    x_min: i64,
+    y_min: i64,
+    largura: i64,
+    altura: i64,
+    custo: Vec<f64>,
+}
+
+impl Grade {
+    fn new(map: &OccupancyMap) -> Self {
+        let (x_min, y_min, x_max, y_max) = map.pixel_bounds;
+        let largura = (x_max - x_min + 1) as i64;
+        let altura = (y_max - y_min + 1) as i64;
+        let mut custo = Vec::with_capacity((largura * altura) as usize);
+        for y in y_min..=y_max {
+            for x in x_min..=x_max {
+                custo.push(map.custo_pixel(x, y));
+            }
+        }
+        Grade { x_min: x_min as i64, y_min: y_min as i64, largura, altura, custo }
+    }
+
+    /// Célula livre? Fora dos limites conta como obstáculo. Coordenadas locais da grade.
+    fn livre(&self, x: i64, y: i64) -> bool {
+        x >= 0 && y >= 0 && x < self.largura && y < self.altura && self.custo[(y * self.largura + x) as usize].is_finite()
+    }
+
+    /// Custo de ir em linha reta do centro de `a` ao centro de `b`: o custo das células integrado ao longo
+    /// do segmento (regra do trapézio, como no `custo_segmento` do mapa). Nas 8 direções da grade percorre
+    /// célula a célula; nas outras amostra o segmento.
+    fn custo_trecho(&self, a: (i64, i64), b: (i64, i64)) -> f64 {
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let passos = if dx == 0 || dy == 0 || dx.abs() == dy.abs() {
+            dx.abs().max(dy.abs())
+        } else {
+            (2.0 * euclidiana(a, b)).ceil() as i64
+        };
+        if passos == 0 {
+            return 0.0;
+        }
+
+        let mut soma = 0.0;
+        for i in 0..=passos {
+            let t = i as f64 / passos as f64;
+            let x = (a.0 as f64 + t * dx as f64).round() as i64;
+            let y = (a.1 as f64 + t * dy as f64).round() as i64;
+            let peso = if i == 0 || i == passos { 0.5 } else { 1.0 };
+            soma += peso * self.custo[self.indice(x, y)];
+        }
+        euclidiana(a, b) * soma / passos as f64
+    }
+
+    fn indice(&self, x: i64, y: i64) -> usize {
+        (y * self.largura + x) as usize
+    }
+
+    fn coordenadas(&self, idx: usize) -> (i64, i64) {
+        (idx as i64 % self.largura, idx as i64 / self.largura)
+    }
+
+    /// Converte um ponto do mapa para a célula local, conferindo se ela é livre
+    fn celula(&self, p: &Point, nome: &str) -> Result<(i64, i64), String> {
+        let (x, y) = (p.x.round() as i64 - self.x_min, p.y.round() as i64 - self.y_min);
+        if !self.livre(x, y) {
+            return Err(format!("O ponto de {} ({}, {}) está fora dos limites ou em um obstáculo.", nome, p.x, p.y));
+        }
+        Ok((x, y))
+    }
+
+    fn ponto(&self, (x, y): (i64, i64)) -> Point {
+        Point { x: (x + self.x_min) as f64, y: (y + self.y_min) as f64 }
+    }
+
+    /// Pode andar de (x, y) um passo na direção (dx, dy)? Diagonais exigem as duas ortogonais livres.
+    fn passo_valido(&self, x: i64, y: i64, dx: i64, dy: i64) -> bool {
+        self.livre(x + dx, y + dy) && (dx == 0 || dy == 0 || (self.livre(x + dx, y) && self.livre(x, y + dy)))
+    }
+
+    /// Vizinhos alcançáveis em um passo
+    fn vizinhos(&self, x: i64, y: i64) -> Vec<(i64, i64)> {
+        let mut v = Vec::with_capacity(8);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if (dx != 0 || dy != 0) && self.passo_valido(x, y, dx, dy) {
+                    v.push((x + dx, y + dy));
+                }
+            }
+        }
+        v
+    }
+
+    /// Linha de visada entre os centros de duas células: percorre todas as células que o segmento atravessa.
+    /// Quando o segmento passa exatamente por uma quina, as duas células da quina precisam estar livres.
+    fn linha_de_visada(&self, (x0, y0): (i64, i64), (x1, y1): (i64, i64)) -> bool {
+        let (dx, dy) = ((x1 - x0).abs(), (y1 - y0).abs());
+        let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+        let (mut x, mut y) = (x0, y0);
+        // Compara os cruzamentos de borda vertical e horizontal em aritmética inteira: (1 + 2i) * dy vs (1 + 2j) * dx
+        let (mut i, mut j) = (0, 0);
+        while i < dx || j < dy {
+            let decisao = (1 + 2 * i) * dy - (1 + 2 * j) * dx;
+            if decisao == 0 {
+                // Passa pela quina: confere as duas células laterais e anda na diagonal
+                if !self.livre(x + sx, y) || !self.livre(x, y + sy) {
+                    return false;
+                }
+                x += sx;
+                y += sy;
+                i += 1;
+                j += 1;
+            } else if decisao < 0 {
+                x += sx;
+                i += 1;
+            } else {
+                y += sy;
+                j += 1;
+            }
+            if !self.livre(x, y) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Distância octil (custo exato em uma grade 8-conexa sem obstáculos)
+fn octil(a: (i64, i64), b: (i64, i64)) -> f64 {
+    let (dx, dy) = ((a.0 - b.0).abs() as f64, (a.1 - b.1).abs() as f64);
+    dx.max(dy) + (SQRT_2 - 1.0) * dx.min(dy)
+}
+
+fn euclidiana(a: (i64, i64), b: (i64, i64)) -> f64 {
+    (((a.0 - b.0).pow(2) + (a.1 - b.1).pow(2)) as f64).sqrt()
+}
+
+/// Item da heap: f = g + h. Empates preferem o maior g (mais perto do destino).
+#[derive(Debug, Clone, Copy)]
+struct Estado {
+    f: f64,
+    g: f64,
+    celula: usize,
+}
+
+impl PartialEq for Estado {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Estado {}
+
+impl Ord for Estado {
+    /// Ordem invertida (BinaryHeap é de máximo), então o menor f sai primeiro
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.f.total_cmp(&self.f)
+            .then_with(|| self.g.total_cmp(&other.g))
+            .then_with(|| other.celula.cmp(&self.celula))
+    }
+}
+
+impl PartialOrd for Estado {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Busca de melhor escolha comum aos três planejadores. `sucessores` recebe a grade, a célula atual e o pai
+/// dela e devolve as células candidatas; `theta` liga o atalho do Theta* (herdar o pai do pai quando há visada).
+fn busca<F>(map: &OccupancyMap, inicio: Point, fim: Point, heuristica: fn((i64, i64), (i64, i64)) -> f64, theta: bool, sucessores: F) -> Result<ResultadoGrade, String>
+where
+    F: Fn(&Grade, (i64, i64), Option<(i64, i64)>, (i64, i64)) -> Vec<(i64, i64)>,
+{
+    let grade = Grade::new(map);
+    let origem = grade.celula(&inicio, "início")?;
+    let destino = grade.celula(&fim, "destino")?;
+
+    let n = grade.custo.len();
+    let mut g = vec![f64::INFINITY; n];
+    let mut pai: Vec<Option<usize>> = vec![None; n];
+    let mut fechado = vec![false; n];
+    let mut heap = BinaryHeap::new();
+    let mut expandidos = 0;
+
+    let i0 = grade.indice(origem.0, origem.1);
+    let i_destino = grade.indice(destino.0, destino.1);
+    g[i0] = 0.0;
+    heap.push(Estado { f: heuristica(origem, destino), g: 0.0, celula: i0 });
+
+    while let Some(Estado { celula, .. }) = heap.pop() {
+        if fechado[celula] {
+            continue;
+        }
+        fechado[celula] = true;
+        expandidos += 1;
+        if celula == i_destino {
+            break;
+        }
+
+        let atual = grade.coordenadas(celula);
+        let pai_atual = pai[celula].map(|p| grade.coordenadas(p));
+        for viz in sucessores(&grade, atual, pai_atual, destino) {
+            let iv = grade.indice(viz.0, viz.1);
+            if fechado[iv] {
+                continue;
+            }
+
+            // Theta*: se o pai da célula atual enxerga o vizinho, liga o vizinho direto nele
+            let (de, custo) = match pai[celula] {
+                Some(p) if theta && grade.linha_de_visada(grade.coordenadas(p), viz) => {
+                    (p, g[p] + grade.custo_trecho(grade.coordenadas(p), viz))
+                }
+                _ => (celula, g[celula] + grade.custo_trecho(atual, viz)),
+            };
+            if custo < g[iv] {
+                g[iv] = custo;
+                pai[iv] = Some(de);
+                heap.push(Estado { f: custo + heuristica(viz, destino), g: custo, celula: iv });
+            }
+        }
+    }
+
+    if !fechado[i_destino] {
+        return Err("Não existe caminho na grade entre o início e o destino.".to_string());
+    }
+
+    // Reconstrói o caminho voltando do destino até a origem
+    let mut caminho = vec![grade.ponto(destino)];
+    let mut atual = i_destino;
+    while let Some(p) = pai[atual] {
+        caminho.push(grade.ponto(grade.coordenadas(p)));
+        atual = p;
+    }
+    caminho.reverse();
+
+    Ok(ResultadoGrade { caminho, custo: g[i_destino], expandidos })
+}
+
+/// A* 8-conexo na grade de pixels, sem cortar quinas. O caminho passa pelo centro de cada célula.
+pub fn a_estrela_grade(map: &OccupancyMap, inicio: Point, fim: Point) -> Result<ResultadoGrade, String> {
+    busca(map, inicio, fim, octil, false, |grade, (x, y), _, _| grade.vizinhos(x, y))
+}
+
+/// Theta* (qualquer ângulo): como o A*, mas cada célula pode ter como pai qualquer célula que ela enxergue,
+/// então o caminho não fica preso às 8 direções da grade.
+pub fn theta_estrela_grade(map: &OccupancyMap, inicio: Point, fim: Point) -> Result<ResultadoGrade, String> {
+    busca(map, inicio, fim, euclidiana, true, |grade, (x, y), _, _| grade.vizinhos(x, y))
+}
+
+/// Jump Point Search com as regras de movimento do A* acima (diagonal só com as duas ortogonais livres).
+/// O caminho tem apenas os pontos de salto; entre eles os trechos são retos ou diagonais.
+pub fn jps_grade(map: &OccupancyMap, inicio: Point, fim: Point) -> Result<ResultadoGrade, String> {
+    busca(map, inicio, fim, octil, false, |grade, atual, pai, destino| {
+        vizinhos_podados(grade, atual, pai)
+            .into_iter()
+            .filter_map(|viz| saltar(grade, viz, atual, destino))
+            .collect()
+    })
+}
+
+/// Vizinhos que o JPS precisa olhar vindo de `pai` (sem pai, todos os vizinhos)
+fn vizinhos_podados(grade: &Grade, (x, y): (i64, i64), pai: Option<(i64, i64)>) -> Vec<(i64, i64)> {
+    let (px, py) = match pai {
+        Some(p) => p,
+        None => return grade.vizinhos(x, y),
+    };
+    let (dx, dy) = ((x - px).signum(), (y - py).signum());
+    let mut v = Vec::with_capacity(5);
+
+    if dx != 0 && dy != 0 {
+        let (vertical, horizontal) = (grade.livre(x, y + dy), grade.livre(x + dx, y));
+        if vertical {
+            v.push((x, y + dy));
+        }
+        if horizontal {
+            v.push((x + dx, y));
+        }
+        if vertical && horizontal {
+            v.push((x + dx, y + dy));
+        }
+    } else if dx != 0 {
+        let (frente, cima, baixo) = (grade.livre(x + dx, y), grade.livre(x, y + 1), grade.livre(x, y - 1));
+        if frente {
+            v.push((x + dx, y));
+            if cima {
+                v.push((x + dx, y + 1));
+            }
+            if baixo {
+                v.push((x + dx, y - 1));
+            }
+        }
+        if cima {
+            v.push((x, y + 1));
+        }
+        if baixo {
+            v.push((x, y - 1));
+        }
+    } else {
+        let (frente, direita, esquerda) = (grade.livre(x, y + dy), grade.livre(x + 1, y), grade.livre(x - 1, y));
+        if frente {
+            v.push((x, y + dy));
+            if direita {
+                v.push((x + 1, y + dy));
+            }
+            if esquerda {
+                v.push((x - 1, y + dy));
+            }
+        }
+        if direita {
+            v.push((x + 1, y));
+        }
+        if esquerda {
+            v.push((x - 1, y));
+        }
+    }
+    v
+}
+
+/// Anda a partir de `de` na direção de `celula` até achar um ponto de salto (destino ou célula com vizinho forçado)
+fn saltar(grade: &Grade, celula: (i64, i64), de: (i64, i64), destino: (i64, i64)) -> Option<(i64, i64)> {
+    let (dx, dy) = (celula.0 - de.0, celula.1 - de.1);
+    let (mut x, mut y) = celula;
+    loop {
+        if !grade.livre(x, y) {
+            return None;
+        }
+        if (x, y) == destino {
+            return Some((x, y));
+        }
+
+        if dx != 0 && dy != 0 {
+            // Na diagonal, é ponto de salto se um salto reto a partir daqui acha algo
+            if saltar(grade, (x + dx, y), (x, y), destino).is_some()
+                || saltar(grade, (x, y + dy), (x, y), destino).is_some()
+            {
+                return Some((x, y));
+            }
+            // Só continua na diagonal se as duas ortogonais estiverem livres
+            if !(grade.livre(x + dx, y) && grade.livre(x, y + dy)) {
+                return None;
+            }
+        } else if dx != 0 {
+            if (grade.livre(x, y - 1) && !grade.livre(x - dx, y - 1))
+                || (grade.livre(x, y + 1) && !grade.livre(x - dx, y + 1))
+            {
+                return Some((x, y));
+            }
+        } else if (grade.livre(x - 1, y) && !grade.livre(x - 1, y - dy))
+            || (grade.livre(x + 1, y) && !grade.livre(x + 1, y - dy))
+        {
+            return Some((x, y));
+        }
+
+        x += dx;
+        y += dy;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readmap::mapa_de_teste;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const EPS: f64 = 1e-9;
+
+    /// Grade 40x30 com ~25% de obstáculos sorteados; os cantos de início e fim ficam sempre livres
+    fn grade_aleatoria(semente: u64) -> OccupancyMap {
+        let (largura, altura) = (40u32, 30u32);
+        let mut rng = StdRng::seed_from_u64(semente);
+        let ocupado: Vec<bool> = (0..largura * altura).map(|_| rng.gen_bool(0.25)).collect();
+        mapa_de_teste(largura, altura, move |x, y| {
+            let canto = (x <= 1 && y <= 1) || (x >= largura - 2 && y >= altura - 2);
+            !canto && ocupado[(y * largura + x) as usize]
+        })
+    }
+
+    fn extremos(map: &OccupancyMap) -> (Point, Point) {
+        let (x_min, y_min, x_max, y_max) = map.pixel_bounds;
+        (Point { x: x_min as f64, y: y_min as f64 }, Point { x: x_max as f64, y: y_max as f64 })
+    }
+
+    #[test]
+    fn jps_tem_o_mesmo_custo_do_a_estrela() {
+        let mut com_caminho = 0;
+        for semente in 0..30 {
+            let map = grade_aleatoria(semente);
+            let (inicio, fim) = extremos(&map);
+            match (a_estrela_grade(&map, inicio, fim), jps_grade(&map, inicio, fim)) {
+                (Ok(a), Ok(j)) => {
+                    assert!((a.custo - j.custo).abs() < EPS, "semente {}: A* {} x JPS {}", semente, a.custo, j.custo);
+                    com_caminho += 1;
+                }
+                (Err(_), Err(_)) => {}
+                (a, j) => panic!("semente {}: só um dos dois achou caminho ({:?} x {:?})", semente, a.is_ok(), j.is_ok()),
+            }
+        }
+        assert!(com_caminho >= 10, "poucas grades com caminho: {}", com_caminho);
+    }
+
+    #[test]
+    fn theta_estrela_nao_e_pior_e_so_anda_com_visada() {
+        for semente in 0..30 {
+            let map = grade_aleatoria(semente);
+            let (inicio, fim) = extremos(&map);
+            let (a, t) = match (a_estrela_grade(&map, inicio, fim), theta_estrela_grade(&map, inicio, fim)) {
+                (Ok(a), Ok(t)) => (a, t),
+                (Err(_), Err(_)) => continue,
+                (a, t) => panic!("semente {}: só um dos dois achou caminho ({:?} x {:?})", semente, a.is_ok(), t.is_ok()),
+            };
+            assert!(t.custo <= a.custo + EPS, "semente {}: Theta* {} > A* {}", semente, t.custo, a.custo);
+
+            let grade = Grade::new(&map);
+            let mut comprimento = 0.0;
+            for par in t.caminho.windows(2) {
+                let (de, ate) = (grade.celula(&par[0], "teste").unwrap(), grade.celula(&par[1], "teste").unwrap());
+                assert!(grade.linha_de_visada(de, ate), "semente {}: trecho {:?} -> {:?} sem visada", semente, par[0], par[1]);
+                comprimento += euclidiana(de, ate);
+            }
+            assert!((comprimento - t.custo).abs() < EPS);
+        }
+    }
+
+    #[test]
+    fn jps_expande_menos_que_a_estrela_em_mapa_aberto() {
+        let map = mapa_de_teste(60, 60, |_, _| false);
+        let (inicio, fim) = (Point { x: 0.0, y: 0.0 }, Point { x: 59.0, y: 30.0 });
+        let a = a_estrela_grade(&map, inicio, fim).unwrap();
+        let j = jps_grade(&map, inicio, fim).unwrap();
+        assert!((a.custo - (29.0 + 30.0 * SQRT_2)).abs() < EPS);
+        assert!((a.custo - j.custo).abs() < EPS);
+        assert!(j.expandidos < a.expandidos, "JPS {} x A* {}", j.expandidos, a.expandidos);
+    }
+
+    #[test]
+    fn extremo_em_obstaculo_ou_sem_caminho_e_erro() {
+        let map = mapa_de_teste(10, 10, |x, _| x == 5);
+        assert!(a_estrela_grade(&map, Point { x: 5.0, y: 2.0 }, Point { x: 8.0, y: 8.0 }).is_err());
+        assert!(jps_grade(&map, Point { x: 1.0, y: 1.0 }, Point { x: 5.0, y: 8.0 }).is_err());
+        assert!(theta_estrela_grade(&map, Point { x: 1.0, y: 1.0 }, Point { x: 8.0, y: 8.0 }).is_err());
+    }
+}
@@ -0,0 +1,294 @@
+
+//! #  Grafo de visibilidade a partir de obstáculos poligonais
+//! Quando os obstáculos são polígonos, o caminho mais curto só faz curvas nos cantos convexos dos obstáculos.
+//! O grafo de visibilidade liga o início, o destino e esses cantos sempre que um enxerga o outro,
+//! então o Dijkstra sobre ele dá o caminho realmente mais curto (o PRM só dá uma aproximação).
+//! Os polígonos podem ser passados diretamente ou extraídos do mapa (ver `contornos`).
+//! ## Output esperado
+//! Um `Graph` comum (pode ser usado pelo `kruskal`, `dijkstra_path`, `path_export` e `save_graph_to_csv`)
+//! com o início no índice `inicio` e o destino no índice `fim`.
+
+#![allow(warnings)]
+
+use crate::structs::{Graph, Point, Poligono, PoligonoComBuracos};
+use crate::readmap::OccupancyMap;
+use crate::contornos::extrair_poligonos;
+
+/// Tolerância numérica das contas geométricas (em pixels)
+const EPS: f64 = 1e-9;
+
+/// Grafo de visibilidade com os índices do início e do destino
+#[derive(Debug)]
+pub struct GrafoVisibilidade {
+    pub graph: Graph,
+    /// Índice do ponto de partida em `graph`
+    pub inicio: usize,
+    /// Índice do ponto de destino em `graph`
+    pub fim: usize,
+}
+
+/// Produto vetorial de (b - a) x (c - a)
+fn cruz(a: &Point, b: &Point, c: &Point) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// Retorna true se `p` está sobre o segmento `ab`
+fn no_segmento(p: &Point, a: &Point, b: &Point) -> bool {
+    cruz(a, b, p).abs() <= EPS * a.dist(b).max(1.0)
+        && p.x >= a.x.min(b.x) - EPS && p.x <= a.x.max(b.x) + EPS
+        && p.y >= a.y.min(b.y) - EPS && p.y <= a.y.max(b.y) + EPS
+}
+
+/// Retorna true se os segmentos `pq` e `ab` se cruzam em um único ponto interior aos dois (cruzamento próprio)
+fn cruzamento_proprio(p: &Point, q: &Point, a: &Point, b: &Point) -> bool {
+    let d1 = cruz(p, q, a);
+    let d2 = cruz(p, q, b);
+    let d3 = cruz(a, b, p);
+    let d4 = cruz(a, b, q);
+    ((d1 > EPS && d2 < -EPS) || (d1 < -EPS && d2 > EPS)) && ((d3 > EPS && d4 < -EPS) || (d3 < -EPS && d4 > EPS))
+}
+
+/// Obstáculos já com os anéis orientados de forma que o obstáculo fique à esquerda de cada aresta:
+/// contorno externo com área positiva e buracos com área negativa
+struct Obstaculos {
+    poligonos: Vec<PoligonoComBuracos>,
+}
+
+impl Obstaculos {
+    fn new(poligonos: &[PoligonoComBuracos]) -> Self {
+        let orientar = |p: &Poligono, area_positiva: bool| {
+            let mut p = p.clone();
+            if (p.area_com_sinal() > 0.0) != area_positiva {
+                p.vertices.reverse();
+            }
+            p
+        };
+        let poligonos = poligonos
+            .iter()
+            .filter(|p| p.externo.vertices.len() >= 3)
+            .map(|p| PoligonoComBuracos {
+                externo: orientar(&p.externo, true),
+                buracos: p.buracos.iter().filter(|b| b.vertices.len() >= 3).map(|b| orientar(b, false)).collect(),
+            })
+            .collect();
+        Obstaculos { poligonos }
+    }
+
+    /// Todos os anéis (contornos externos e buracos)
+    fn aneis(&self) -> impl Iterator<Item = &Vec<Point>> {
+        self.poligonos.iter().flat_map(|p| std::iter::once(&p.externo.vertices).chain(p.buracos.iter().map(|b| &b.vertices)))
+    }
+
+    /// Todas as arestas de todos os anéis
+    fn arestas(&self) -> impl Iterator<Item = (&Point, &Point)> {
+        self.aneis().flat_map(|anel| {
+            (0..anel.len()).map(move |i| (&anel[i], &anel[(i + 1) % anel.len()]))
+        })
+    }
+
+    /// Retorna true se `p` está estritamente dentro de algum obstáculo (a borda conta como livre)
+    fn dentro(&self, p: &Point) -> bool {
+        if self.arestas().any(|(a, b)| no_segmento(p, a, b)) {
+            return false;
+        }
+        self.poligonos.iter().any(|poligono| poligono.contem(p))
+    }
+
+    /// Cantos convexos dos obstáculos (curva para a esquerda, já que o obstáculo está à esquerda)
+    fn cantos_convexos(&self) -> Vec<Point> {
+        let mut cantos = Vec::new();
+        for anel in self.aneis() {
+            let n = anel.len();
+            for i in 0..n {
+                let (a, b, c) = (&anel[(i + n - 1) % n], &anel[i], &anel[(i + 1) % n]);
+                if cruz(a, b, c) > EPS {
+                    cantos.push(*b);
+                }
+            }
+        }
+        cantos
+    }
+
+    /// Retorna true se o segmento `pq` não passa por dentro de nenhum obstáculo (pode encostar nas bordas)
+    fn visivel(&self, p: &Point, q: &Point) -> bool {
+        // Parâmetros t em [0, 1] onde o segmento encosta em alguma aresta ou vértice
+        let mut cortes = vec![0.0, 1.0];
+        let comprimento2 = (q.x - p.x).powi(2) + (q.y - p.y).powi(2);
+        if comprimento2 <= EPS {
+            return !self.dentro(p);
+        }
+        let parametro = |c: &Point| ((c.x - p.x) * (q.x - p.x) + (c.y - p.y) * (q.y - p.y)) / comprimento2;
+
+        for (a, b) in self.arestas() {
+            if cruzamento_proprio(p, q, a, b) {
+                return false;
+            }
+            // Vértices dos obstáculos sobre o segmento dividem o segmento em pedaços
+            for c in [a, b] {
+                if no_segmento(c, p, q) {
+                    cortes.push(parametro(c));
+                }
+            }
+        }
+
+        // Cada pedaço entre dois cortes está todo dentro ou todo fora; basta testar o ponto médio
+        cortes.sort_by(|a, b| a.total_cmp(b));
+        cortes.windows(2).all(|t| {
+            let meio = (t[0] + t[1]) / 2.0;
+            t[1] - t[0] <= EPS || !self.dentro(&Point { x: p.x + meio * (q.x - p.x), y: p.y + meio * (q.y - p.y) })
+        })
+    }
+}
+
+/// Monta o grafo de visibilidade entre `inicio`, `fim` e os cantos convexos dos `poligonos`.
+/// Os polígonos podem estar em qualquer sentido. Retorna erro se o início ou o destino estiverem dentro de um obstáculo.
+pub fn grafo_visibilidade(poligonos: &[Poligono], inicio: Point, fim: Point) -> Result<GrafoVisibilidade, String> {
+    let sem_buracos: Vec<PoligonoComBuracos> = poligonos
+        .iter()
+        .map(|p| PoligonoComBuracos { externo: p.clone(), buracos: Vec::new() })
+        .collect();
+    grafo_visibilidade_com_buracos(&sem_buracos, inicio, fim)
+}
+
+/// Igual ao `grafo_visibilidade`, mas com obstáculos que têm buracos (regiões livres dentro deles),
+/// como a moldura preta do mapa, cujo buraco é toda a área navegável.
+pub fn grafo_visibilidade_com_buracos(poligonos: &[PoligonoComBuracos], inicio: Point, fim: Point) -> Result<GrafoVisibilidade, String> {
+    montar(&Obstaculos::new(poligonos), inicio, fim)
+}
+
+/// Extrai os obstáculos do mapa como polígonos (simplificados com tolerância `epsilon`, em pixels)
+/// e monta o grafo de visibilidade entre `inicio` e `fim`.
+/// Como os caminhos encostam nos cantos dos obstáculos, que passam pelo centro dos pixels de borda,
+/// o `is_path_colliding` pode acusar colisão nesses trechos.
+pub fn grafo_visibilidade_do_mapa(map: &OccupancyMap, epsilon: f64, inicio: Point, fim: Point) -> Result<GrafoVisibilidade, String> {
+    grafo_visibilidade_com_buracos(&extrair_poligonos(map, epsilon), inicio, fim)
+}
+
+fn montar(obstaculos: &Obstaculos, inicio: Point, fim: Point) -> Result<GrafoVisibilidade, String> {
+    if obstaculos.dentro(&inicio) {
+        return Err("O ponto de partida está dentro de um obstáculo.".to_string());
+    }
+    if obstaculos.dentro(&fim) {
+        return Err("O ponto de destino está dentro de um obstáculo.".to_string());
+    }
+
+    let mut graph = Graph::new();
+    let idx_inicio = graph.add_vertex(inicio);
+    let idx_fim = graph.add_vertex(fim);
+    // Cantos dentro de outro obstáculo (polígonos sobrepostos) nunca são visíveis
+    for canto in obstaculos.cantos_convexos() {
+        if !obstaculos.dentro(&canto) {
+            graph.add_vertex(canto);
+        }
+    }
+
+    // Liga todo par de vértices que se enxerga
+    let vertices = graph.vertices.clone();
+    for i in 0..vertices.len() {
+        for j in (i + 1)..vertices.len() {
+            if obstaculos.visivel(&vertices[i], &vertices[j]) {
+                graph.add_edge_dist(i, j);
+            }
+        }
+    }
+
+    Ok(GrafoVisibilidade { graph, inicio: idx_inicio, fim: idx_fim })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dijkstra::dijkstra_path;
+    use crate::readmap::mapa_de_teste;
+
+    fn retangulo(x0: f64, y0: f64, x1: f64, y1: f64) -> Poligono {
+        let vertices = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)].iter().map(|&(x, y)| Point { x, y }).collect();
+        Poligono { vertices }
+    }
+
+    /// Caminho mais curto (pelo Dijkstra) e o comprimento dele
+    fn menor_caminho(g: &GrafoVisibilidade) -> Option<(Vec<Point>, f64)> {
+        let caminho = dijkstra_path(&g.graph, g.inicio, g.fim)?;
+        let pontos: Vec<Point> = caminho.iter().map(|&i| g.graph.vertices[i]).collect();
+        let comprimento = pontos.windows(2).map(|par| par[0].dist(&par[1])).sum();
+        Some((pontos, comprimento))
+    }
+
+    /// Anel formado por quatro barras sobrepostas de largura 4; o espaço livre dentro dele vai de 4 a 16
+    fn anel() -> Vec<Poligono> {
+        vec![
+            retangulo(0.0, 0.0, 20.0, 4.0),
+            retangulo(0.0, 16.0, 20.0, 20.0),
+            retangulo(0.0, 0.0, 4.0, 20.0),
+            retangulo(16.0, 0.0, 20.0, 20.0),
+        ]
+    }
+
+    #[test]
+    fn desvio_de_um_quadrado() {
+        // Contornar o quadrado 10x10 passando por dois cantos: 2 diagonais de 5√2 e um lado de 10
+        let quadrado = retangulo(10.0, 10.0, 20.0, 20.0);
+        // O sentido dos vértices não importa
+        let invertido = Poligono { vertices: quadrado.vertices.iter().rev().copied().collect() };
+        for obstaculo in [quadrado, invertido] {
+            let g = grafo_visibilidade(&[obstaculo], Point { x: 5.0, y: 15.0 }, Point { x: 25.0, y: 15.0 }).unwrap();
+            let (pontos, comprimento) = menor_caminho(&g).unwrap();
+            assert!((comprimento - (10.0 + 10.0 * 2f64.sqrt())).abs() < 1e-9, "comprimento {}", comprimento);
+            assert_eq!(pontos.len(), 4);
+        }
+
+        // Sem obstáculo no caminho, a linha reta
+        let g = grafo_visibilidade(&[retangulo(10.0, 10.0, 20.0, 20.0)], Point { x: 5.0, y: 5.0 }, Point { x: 25.0, y: 5.0 }).unwrap();
+        assert_eq!(menor_caminho(&g).unwrap().1, 20.0);
+    }
+
+    #[test]
+    fn caminho_dentro_do_anel_fica_dentro() {
+        let g = grafo_visibilidade(&anel(), Point { x: 5.0, y: 5.0 }, Point { x: 15.0, y: 15.0 }).unwrap();
+        let (pontos, comprimento) = menor_caminho(&g).unwrap();
+        assert!((comprimento - 200f64.sqrt()).abs() < 1e-9);
+        assert!(pontos.iter().all(|p| p.x >= 4.0 && p.x <= 16.0 && p.y >= 4.0 && p.y <= 16.0), "{:?}", pontos);
+    }
+
+    #[test]
+    fn sem_caminho_entre_dentro_e_fora_do_anel() {
+        let g = grafo_visibilidade(&anel(), Point { x: 25.0, y: 10.0 }, Point { x: 10.0, y: 10.0 }).unwrap();
+        assert!(menor_caminho(&g).is_none());
+    }
+
+    #[test]
+    fn ponto_dentro_de_obstaculo_e_erro() {
+        let obstaculos = [retangulo(10.0, 10.0, 20.0, 20.0)];
+        let livre = Point { x: 5.0, y: 5.0 };
+        let dentro = Point { x: 15.0, y: 12.0 };
+        assert!(grafo_visibilidade(&obstaculos, dentro, livre).is_err());
+        assert!(grafo_visibilidade(&obstaculos, livre, dentro).is_err());
+        // Na borda conta como livre
+        assert!(grafo_visibilidade(&obstaculos, Point { x: 10.0, y: 15.0 }, livre).is_ok());
+    }
+
+    #[test]
+    fn buraco_de_um_poligono_e_livre() {
+        // Mesmo anel, agora como um único polígono com buraco
+        let anel = PoligonoComBuracos { externo: retangulo(0.0, 0.0, 20.0, 20.0), buracos: vec![retangulo(4.0, 4.0, 16.0, 16.0)] };
+        let g = grafo_visibilidade_com_buracos(&[anel.clone()], Point { x: 5.0, y: 15.0 }, Point { x: 15.0, y: 5.0 }).unwrap();
+        let (pontos, _) = menor_caminho(&g).unwrap();
+        assert_eq!(pontos.len(), 2);
+
+        let g = grafo_visibilidade_com_buracos(&[anel.clone()], Point { x: 25.0, y: 10.0 }, Point { x: 10.0, y: 10.0 }).unwrap();
+        assert!(menor_caminho(&g).is_none());
+        assert!(grafo_visibilidade_com_buracos(&[anel], Point { x: 2.0, y: 10.0 }, Point { x: 10.0, y: 10.0 }).is_err());
+    }
+
+    #[test]
+    fn desvio_no_mapa_extraido() {
+        // Moldura 40x30 com um bloco no meio (x de 15 a 24, y de 10 a 19): a moldura vira um polígono com buraco
+        let map = mapa_de_teste(40, 30, |x, y| x == 0 || y == 0 || x == 39 || y == 29 || ((15..=24).contains(&x) && (10..=19).contains(&y)));
+        let g = grafo_visibilidade_do_mapa(&map, 0.5, Point { x: 5.0, y: 15.0 }, Point { x: 35.0, y: 15.0 }).unwrap();
+        let (pontos, comprimento) = menor_caminho(&g).unwrap();
+        // O contorno passa pelo centro dos pixels de borda: contorna o bloco pelos cantos de baixo, (15, 19) e (24, 19)
+        let esperado = 116f64.sqrt() + 9.0 + 137f64.sqrt();
+        assert!((comprimento - esperado).abs() < 1e-9, "comprimento {} em {:?}", comprimento, pontos);
+        assert_eq!(pontos[1], Point { x: 15.0, y: 19.0 });
+    }
+}
@@ -0,0 +1,255 @@
+
+//! #  Lazy PRM: checagem de colisão adiada para a hora da consulta
+//! O `generate_random_graph` checa colisão de todos os pares candidatos, o que domina o tempo de construção.
+//! No Lazy PRM as arestas entram sem checagem; numa consulta só as arestas do caminho candidato são conferidas.
+//! As que colidem são removidas do grafo e o caminho é recalculado até achar um caminho válido (ou nenhum).
+//! Antes de conferidas as arestas pesam só o comprimento; as livres passam a pesar o `custo_segmento` do mapa
+//! (camada de custo, células desconhecidas), que nunca é menor que o comprimento.
+//! Consultas entre pontos quaisquer (`query_pontos`) também ligam o início e o destino ao roadmap sem checagem.
+
+#![allow(warnings)]
+
+use crate::readmap::OccupancyMap;
+use crate::structs::{Graph, Peso, Point};
+use crate::prm_generator::{generate_prm_lazy, passos_colisao, ModoConexao};
+use crate::dijkstra::dijkstra_path;
+use std::collections::HashSet;
+
+/// Roadmap do Lazy PRM. Guarda quais arestas já foram conferidas para não checar de novo em outras consultas.
+#[derive(Debug)]
+pub struct LazyPrm {
+    /// O grafo, que vai perdendo as arestas que colidem conforme as consultas são feitas
+    pub graph: Graph,
+    /// Pares (menor, maior) de arestas já conferidas e livres
+    validadas: HashSet<(usize, usize)>,
+    /// Quantas arestas já foram removidas por colisão
+    pub arestas_removidas: usize,
+}
+
+impl LazyPrm {
+    /// Gera o roadmap sem checar colisão das arestas
+    pub fn new(map: &OccupancyMap, num_vertices: usize, modo: ModoConexao) -> Self {
+        LazyPrm::from_graph(generate_prm_lazy(map, num_vertices, modo))
+    }
+
+    /// Usa um grafo já existente como roadmap do Lazy PRM (nenhuma aresta é considerada conferida)
+    pub fn from_graph(graph: Graph) -> Self {
+        LazyPrm { graph, validadas: HashSet::new(), arestas_removidas: 0 }
+    }
+
+    /// Procura um caminho livre entre dois pontos quaisquer do mapa. Os pontos entram no roadmap como vértices
+    /// temporários, ligados sem checagem de colisão segundo o `modo`: a todos os vértices no raio ou aos k mais
+    /// próximos. Depois segue como o `query` e, no fim, tira os vértices temporários do grafo; o que foi
+    /// descoberto sobre as arestas do roadmap (validadas ou removidas) continua valendo para as próximas consultas.
+    /// Retorna os pontos do caminho, do início ao destino.
+    pub fn query_pontos(&mut self, map: &OccupancyMap, inicio: Point, fim: Point, modo: ModoConexao) -> Result<Vec<Point>, String> {
+        if map.is_obstructed(&inicio) {
+            return Err("O ponto de partida está em um obstáculo.".to_string());
+        }
+        if map.is_obstructed(&fim) {
+            return Err("O ponto de destino está em um obstáculo.".to_string());
+        }
+
+        let n = self.graph.vertices.len();
+        let modo = modo.resolver(map, n + 2);
+        let start = self.conectar_ponto(inicio, modo);
+        let end = self.conectar_ponto(fim, modo);
+        let caminho = self.query(map, start, end).map(|path| path.iter().map(|&i| self.graph.vertices[i]).collect());
+        self.remover_temporarios(n);
+        caminho.ok_or_else(|| "Nenhum caminho livre entre o início e o destino no roadmap.".to_string())
+    }
+
+    /// Tira do grafo os vértices a partir de `n` (os temporários de uma consulta) e todas as arestas deles
+    fn remover_temporarios(&mut self, n: usize) {
+        for lista in self.graph.adj.iter_mut().take(n) {
+            lista.retain(|e| e.to_idx < n);
+        }
+        self.graph.vertices.truncate(n);
+        self.graph.adj.truncate(n);
+        self.validadas.retain(|&(_, maior)| maior < n);
+    }
+
+    /// Adiciona `p` ao grafo e o liga (sem checar colisão) aos vértices escolhidos pelo `modo` já resolvido
+    fn conectar_ponto(&mut self, p: Point, modo: ModoConexao) -> usize {
+        let mut candidatos: Vec<(f64, usize)> = self.graph.vertices.iter().enumerate().map(|(i, v)| (v.dist(&p), i)).collect();
+        candidatos.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+        let escolhidos: Vec<usize> = match modo {
+            ModoConexao::Raio(r) => candidatos.into_iter().take_while(|&(dist, _)| dist <= r).map(|(_, j)| j).collect(),
+            ModoConexao::KVizinhos { k, .. } => candidatos.into_iter().take(k).map(|(_, j)| j).collect(),
+            _ => unreachable!("O modo já foi resolvido."),
+        };
+
+        let idx = self.graph.add_vertex(p);
+        for j in escolhidos {
+            self.graph.add_edge_dist(idx, j);
+        }
+        idx
+    }
+
+    /// Procura um caminho livre entre os vértices `start` e `end`.
+    /// Confere só as arestas do menor caminho atual; se alguma colidir, remove e calcula outro caminho.
+    /// As livres recebem o custo do mapa como peso; se algum peso mudou, o caminho também é recalculado,
+    /// pois outro caminho pode ter ficado mais barato.
+    pub fn query(&mut self, map: &OccupancyMap, start: usize, end: usize) -> Option<Vec<usize>> {
+        loop {
+            // Menor caminho no grafo atual (ainda com arestas não conferidas)
+            let path = dijkstra_path(&self.graph, start, end)?;
+
+            let mut colidiu = false;
+            let mut reponderou = false;
+            for par in path.windows(2) {
+                let (u, v) = (par[0], par[1]);
+                let chave = (u.min(v), u.max(v));
+                if self.validadas.contains(&chave) {
+                    continue;
+                }
+
+                let p1 = self.graph.vertices[u];
+                let p2 = self.graph.vertices[v];
+                let passos = passos_colisao(p1.dist(&p2));
+                // Uma aresta sem colisão ainda pode ter custo infinito (multiplicador de desconhecido enorme)
+                let peso = if map.is_path_colliding(&p1, &p2, passos) {
+                    None
+                } else {
+                    Peso::new(map.custo_segmento(&p1, &p2, passos)).ok()
+                };
+                match peso {
+                    Some(peso) => {
+                        self.validadas.insert(chave);
+                        reponderou |= self.definir_peso(u, v, peso);
+                    }
+                    None => {
+                        // Aresta inválida: sai do grafo para as próximas buscas
+                        self.graph.remove_edge(u, v);
+                        self.arestas_removidas += 1;
+                        colidiu = true;
+                    }
+                }
+            }
+
+            // Todas as arestas do caminho estão livres e com o custo do mapa
+            if !colidiu && !reponderou {
+                return Some(path);
+            }
+        }
+    }
+
+    /// Troca o peso da aresta (u, v) nos dois sentidos. Retorna true se o peso mudou.
+    fn definir_peso(&mut self, u: usize, v: usize, peso: Peso) -> bool {
+        let mut mudou = false;
+        for (a, b) in [(u, v), (v, u)] {
+            for e in self.graph.adj[a].iter_mut().filter(|e| e.to_idx == b) {
+                mudou |= e.weight != peso;
+                e.weight = peso;
+            }
+        }
+        mudou
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camada_custo::FonteCusto;
+    use crate::readmap::mapa_de_teste;
+    use image::{DynamicImage, ImageBuffer, Rgb};
+    use crate::structs::Point;
+
+    /// Mapa 40x30 com uma parede de x = 19 a 21 que só deixa passagem embaixo (y de 21 a 28)
+    fn mapa_com_parede() -> OccupancyMap {
+        mapa_de_teste(40, 30, |x, y| x == 0 || y == 0 || x == 39 || y == 29 || ((19..=21).contains(&x) && y <= 20))
+    }
+
+    /// Roadmap com a aresta curta 0-1 atravessando a parede e o desvio 0-2-3-1 pela passagem
+    fn roadmap_com_desvio() -> Graph {
+        let mut gr = Graph::new();
+        for (x, y) in [(5.0, 5.0), (35.0, 5.0), (10.0, 25.0), (30.0, 25.0)] {
+            gr.add_vertex(Point { x, y });
+        }
+        for (u, v) in [(0, 1), (0, 2), (2, 3), (3, 1)] {
+            let dist = gr.vertices[u].dist(&gr.vertices[v]);
+            gr.add_edge(u, v, dist).unwrap();
+        }
+        gr
+    }
+
+    #[test]
+    fn remove_a_aresta_que_colide_e_replaneja() {
+        let map = mapa_com_parede();
+        let mut lazy = LazyPrm::from_graph(roadmap_com_desvio());
+
+        assert_eq!(lazy.query(&map, 0, 1), Some(vec![0, 2, 3, 1]));
+        assert_eq!(lazy.arestas_removidas, 1);
+        assert!(lazy.graph.adj[0].iter().all(|e| e.to_idx != 1), "a aresta 0-1 deveria ter saído do grafo");
+        assert!(lazy.graph.adj[1].iter().all(|e| e.to_idx != 0));
+    }
+
+    #[test]
+    fn aresta_validada_nao_e_conferida_de_novo() {
+        let mut lazy = LazyPrm::from_graph(roadmap_com_desvio());
+        let caminho = lazy.query(&mapa_com_parede(), 0, 1).unwrap();
+
+        // Num mapa todo bloqueado qualquer checagem colidiria: o caminho só volta porque as arestas já foram validadas
+        let bloqueado = mapa_de_teste(40, 30, |_, _| true);
+        assert_eq!(lazy.query(&bloqueado, 1, 0), Some(caminho.into_iter().rev().collect()));
+        assert_eq!(lazy.arestas_removidas, 1);
+    }
+
+    #[test]
+    fn sem_caminho_depois_de_cortar_o_roadmap() {
+        let map = mapa_com_parede();
+        let mut gr = roadmap_com_desvio();
+        // Sem a passagem por baixo só sobra a aresta que atravessa a parede
+        gr.remove_edge(2, 3);
+        let mut lazy = LazyPrm::from_graph(gr);
+
+        assert_eq!(lazy.query(&map, 0, 1), None);
+        assert_eq!(lazy.arestas_removidas, 1);
+        // As consultas seguintes continuam sem caminho e não removem mais nada
+        assert_eq!(lazy.query(&map, 1, 0), None);
+        assert_eq!(lazy.arestas_removidas, 1);
+        assert_eq!(lazy.query(&map, 0, 2), Some(vec![0, 2]));
+    }
+
+    #[test]
+    fn aresta_validada_passa_a_pesar_o_custo_do_mapa() {
+        // Sem parede, mas com lama (custo 10) ao longo da aresta direta 0-1
+        let mut map = mapa_de_teste(40, 30, |x, y| x == 0 || y == 0 || x == 39 || y == 29);
+        let lama = DynamicImage::ImageRgb8(ImageBuffer::from_fn(40, 30, |_, y| if y <= 8 { Rgb([120, 80, 0]) } else { Rgb([255, 255, 255]) }));
+        let fonte = FonteCusto::Legenda { cores: vec![([120, 80, 0], 10.0)], tolerancia: 0, padrao: 1.0 };
+        map.definir_camada_custo(&lama, &fonte).unwrap();
+
+        let mut lazy = LazyPrm::from_graph(roadmap_com_desvio());
+        // A aresta direta é a mais curta, mas depois de conferida pesa ~300 e o desvio (~61) fica mais barato
+        assert_eq!(lazy.query(&map, 0, 1), Some(vec![0, 2, 3, 1]));
+        assert_eq!(lazy.arestas_removidas, 0);
+        let direta = lazy.graph.adj[0].iter().find(|e| e.to_idx == 1).unwrap().weight.valor();
+        assert!((direta - 300.0).abs() < 1e-9, "peso da aresta direta: {}", direta);
+        let desvio = lazy.graph.adj[2].iter().find(|e| e.to_idx == 3).unwrap().weight.valor();
+        assert!((desvio - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn query_pontos_nao_deixa_vertices_no_roadmap() {
+        let map = mapa_com_parede();
+        let mut lazy = LazyPrm::from_graph(roadmap_com_desvio());
+        let modo = ModoConexao::KVizinhos { k: 1, mutuo: false };
+        let (inicio, fim) = (Point { x: 6.0, y: 7.0 }, Point { x: 34.0, y: 7.0 });
+
+        let caminho = lazy.query_pontos(&map, inicio, fim, modo).unwrap();
+        let v = lazy.graph.vertices.clone();
+        let esperado = vec![inicio, v[0], v[2], v[3], v[1], fim];
+        assert_eq!(caminho, esperado);
+        assert_eq!(lazy.graph.vertices.len(), 4);
+        assert_eq!(lazy.graph.adj.len(), 4);
+        assert!(lazy.graph.adj.iter().flatten().all(|e| e.to_idx < 4));
+        assert_eq!(lazy.arestas_removidas, 1);
+
+        // A segunda consulta reaproveita o que a primeira aprendeu e também não deixa rastro
+        assert_eq!(lazy.query_pontos(&map, inicio, fim, modo).unwrap(), esperado);
+        assert_eq!((lazy.graph.vertices.len(), lazy.arestas_removidas), (4, 1));
+
+        assert!(lazy.query_pontos(&map, Point { x: 20.0, y: 10.0 }, fim, modo).is_err());
+        assert_eq!(lazy.graph.vertices.len(), 4);
+    }
+}
@@ -3,10 +3,30 @@ pub mod case_first;
 pub mod case_second;
 pub mod structs;
 pub mod readmap;
+pub mod classificador;
+pub mod camada_custo;
+pub mod mapa_ros;
+pub mod movingai;
+pub mod gerador_mapas;
+pub mod reparo;
+pub mod amostragem;
 pub mod prm_generator;
+pub mod lazy_prm;
+pub mod rrt;
+pub mod rrt_estrela;
+pub mod contornos;
+pub mod grafo_visibilidade;
+pub mod voronoi;
+pub mod grade;
+pub mod dsu;
 pub mod kruskal;
+pub mod prim;
+pub mod boruvka;
+pub mod agm;
 pub mod dfs;
+pub mod dijkstra;
 pub mod read_graph;
+pub mod validacao;
 pub mod vertice_mais_proximo;
 pub mod read_coord;
 
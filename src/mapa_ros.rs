@@ -0,0 +1,274 @@
+
+//! #  Mapas no formato do map_server do ROS (YAML + imagem PGM/PNG) e coordenadas em metros
+//! O YAML descreve a imagem e como interpretá-la:
+//! ```yaml
+//! image: mapa.pgm
+//! resolution: 0.05          # metros por pixel
+//! origin: [-10.0, -10.0, 0.0] # pose (x, y, yaw) do canto inferior esquerdo da imagem
+//! occupied_thresh: 0.65
+//! free_thresh: 0.196
+//! negate: 0
+//! ```
+//! A leitura do mapa em si fica no `OccupancyMap::de_yaml_ros`; aqui ficam o parser do YAML e a
+//! `TransformacaoMundo`, que converte pontos, grafos e caminhos entre pixels e metros.
+//! ## Output esperado
+//! Grafos e caminhos em metros, que podem ser salvos com `save_graph_to_csv` / `path_export_pontos`,
+//! e pontos em metros convertidos para pixels antes das consultas.
+
+#![allow(warnings)]
+
+use crate::structs::{Graph, Point};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Conteúdo do YAML do map_server
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigMapaRos {
+    /// Caminho da imagem, já resolvido em relação à pasta do YAML
+    pub imagem: PathBuf,
+    /// Metros por pixel
+    pub resolucao: f64,
+    /// Pose (x, y, yaw) do canto inferior esquerdo da imagem no mundo
+    pub origem: (f64, f64, f64),
+    pub limiar_ocupado: f64,
+    pub limiar_livre: f64,
+    /// Se true, pixels claros é que são obstáculo
+    pub negar: bool,
+}
+
+/// Tira comentários e aspas de um valor do YAML
+fn limpar_valor(valor: &str) -> &str {
+    let valor = match valor.find(" #") {
+        Some(i) => &valor[..i],
+        None => valor,
+    };
+    valor.trim().trim_matches(|c| c == '"' || c == '\'')
+}
+
+fn ler_numero(campos: &HashMap<String, String>, chave: &str) -> Result<f64, String> {
+    let valor = campos.get(chave).ok_or_else(|| format!("Campo '{}' ausente no YAML.", chave))?;
+    valor.parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| format!("Campo '{}' não é um número: '{}'.", chave, valor))
+}
+
+/// Lê o YAML do map_server. Só entende o subconjunto usado por esses arquivos: um `chave: valor` por linha,
+/// comentários com `#` e a origem como lista `[x, y, yaw]`. O modo `trinary` (padrão) e o `scale` são lidos
+/// como três estados; o modo `raw` não é suportado.
+pub fn ler_yaml_ros(yaml_path: &str) -> Result<ConfigMapaRos, String> {
+    let texto = std::fs::read_to_string(yaml_path)
+        .map_err(|e| format!("Não foi possível ler o YAML '{}': {}", yaml_path, e))?;
+
+    let mut campos: HashMap<String, String> = HashMap::new();
+    for (n, linha) in texto.lines().enumerate() {
+        let linha = linha.trim();
+        if linha.is_empty() || linha.starts_with('#') {
+            continue;
+        }
+        let (chave, valor) = linha
+            .split_once(':')
+            .ok_or_else(|| format!("Linha {} do YAML não é 'chave: valor': '{}'.", n + 1, linha))?;
+        campos.insert(chave.trim().to_string(), limpar_valor(valor).to_string());
+    }
+
+    let imagem = campos.get("image").ok_or("Campo 'image' ausente no YAML.")?;
+    // Caminhos relativos são relativos à pasta do YAML, como no map_server
+    let pasta = Path::new(yaml_path).parent().unwrap_or(Path::new(""));
+    let imagem = pasta.join(imagem);
+
+    let resolucao = ler_numero(&campos, "resolution")?;
+    if resolucao <= 0.0 {
+        return Err(format!("A resolução precisa ser positiva, veio {}.", resolucao));
+    }
+
+    let origem = campos.get("origin").ok_or("Campo 'origin' ausente no YAML.")?;
+    let valores: Vec<f64> = origem
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|v| v.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Origem inválida: '{}'.", origem))?;
+    if valores.len() != 3 || valores.iter().any(|v| !v.is_finite()) {
+        return Err(format!("A origem precisa ser [x, y, yaw], veio '{}'.", origem));
+    }
+
+    let limiar_ocupado = ler_numero(&campos, "occupied_thresh")?;
+    let limiar_livre = ler_numero(&campos, "free_thresh")?;
+    if !(0.0 <= limiar_livre && limiar_livre <= limiar_ocupado && limiar_ocupado <= 1.0) {
+        return Err(format!("Limiares inválidos: free_thresh = {}, occupied_thresh = {}.", limiar_livre, limiar_ocupado));
+    }
+
+    let negar = match campos.get("negate").map(|s| s.as_str()) {
+        None | Some("0") | Some("false") => false,
+        Some("1") | Some("true") => true,
+        Some(outro) => return Err(format!("Valor de 'negate' inválido: '{}'.", outro)),
+    };
+
+    match campos.get("mode").map(|s| s.as_str()) {
+        None | Some("trinary") | Some("scale") => {}
+        Some(outro) => return Err(format!("Modo '{}' não suportado (use 'trinary' ou 'scale').", outro)),
+    }
+
+    Ok(ConfigMapaRos {
+        imagem,
+        resolucao,
+        origem: (valores[0], valores[1], valores[2]),
+        limiar_ocupado,
+        limiar_livre,
+        negar,
+    })
+}
+
+/// Conversão entre coordenadas de pixel (as usadas pelo `OccupancyMap`, y para baixo, pixel inteiro no centro)
+/// e coordenadas do mundo em metros (y para cima, com a origem e o yaw do YAML)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransformacaoMundo {
+    /// Metros por pixel
+    pub resolucao: f64,
+    /// Pose (x, y, yaw) do canto inferior esquerdo da imagem no mundo
+    pub origem: (f64, f64, f64),
+    /// Altura da imagem em pixels (para inverter o eixo y)
+    pub altura: u32,
+}
+
+impl TransformacaoMundo {
+    /// Ponto em pixels para metros
+    pub fn para_mundo(&self, p: &Point) -> Point {
+        // Coordenadas no referencial do mapa (canto inferior esquerdo, y para cima)
+        let mx = (p.x + 0.5) * self.resolucao;
+        let my = (self.altura as f64 - p.y - 0.5) * self.resolucao;
+        let (ox, oy, yaw) = self.origem;
+        let (sen, cos) = yaw.sin_cos();
+        Point { x: ox + cos * mx - sen * my, y: oy + sen * mx + cos * my }
+    }
+
+    /// Ponto em metros para pixels (inversa de `para_mundo`)
+    pub fn para_pixel(&self, p: &Point) -> Point {
+        let (ox, oy, yaw) = self.origem;
+        let (sen, cos) = yaw.sin_cos();
+        let (dx, dy) = (p.x - ox, p.y - oy);
+        let mx = cos * dx + sen * dy;
+        let my = -sen * dx + cos * dy;
+        Point { x: mx / self.resolucao - 0.5, y: self.altura as f64 - my / self.resolucao - 0.5 }
+    }
+
+    /// Copia o grafo com os vértices em metros e os pesos multiplicados pela resolução
+    pub fn grafo_para_mundo(&self, gr: &Graph) -> Result<Graph, String> {
+        self.converter_grafo(gr, |p| self.para_mundo(p), self.resolucao)
+    }
+
+    /// Copia o grafo com os vértices em pixels e os pesos divididos pela resolução
+    pub fn grafo_para_pixels(&self, gr: &Graph) -> Result<Graph, String> {
+        self.converter_grafo(gr, |p| self.para_pixel(p), 1.0 / self.resolucao)
+    }
+
+    fn converter_grafo(&self, gr: &Graph, converter: impl Fn(&Point) -> Point, escala: f64) -> Result<Graph, String> {
+        let mut novo = Graph::new();
+        for v in &gr.vertices {
+            novo.add_vertex(converter(v));
+        }
+        for edge in gr.get_undirected_edges() {
+            novo.add_edge(edge.from, edge.to, edge.weight.valor() * escala)?;
+        }
+        Ok(novo)
+    }
+
+    /// Caminho (lista de pontos em pixels) para metros
+    pub fn caminho_para_mundo(&self, caminho: &[Point]) -> Vec<Point> {
+        caminho.iter().map(|p| self.para_mundo(p)).collect()
+    }
+
+    /// Caminho (lista de pontos em metros) para pixels
+    pub fn caminho_para_pixels(&self, caminho: &[Point]) -> Vec<Point> {
+        caminho.iter().map(|p| self.para_pixel(p)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classificador::EstadoCelula;
+    use crate::readmap::OccupancyMap;
+    use image::{ImageBuffer, Rgb};
+
+    /// Pasta temporária só deste teste (os testes rodam em paralelo)
+    fn pasta_teste(nome: &str) -> PathBuf {
+        let pasta = std::env::temp_dir().join(format!("prm_mapa_ros_{}_{}", nome, std::process::id()));
+        std::fs::create_dir_all(&pasta).unwrap();
+        pasta
+    }
+
+    fn escrever_yaml(pasta: &Path, conteudo: &str) -> String {
+        let caminho = pasta.join("mapa.yaml");
+        std::fs::write(&caminho, conteudo).unwrap();
+        caminho.to_string_lossy().into_owned()
+    }
+
+    const YAML: &str = "# mapa de teste\n\
+        image: \"mapa.png\"\n\
+        resolution: 0.05 # metros por pixel\n\
+        origin: [-1.0, 2.5, 0.0]\n\
+        occupied_thresh: 0.65\n\
+        free_thresh: 0.196\n\
+        negate: 0\n";
+
+    #[test]
+    fn le_os_campos_do_yaml() {
+        let pasta = pasta_teste("campos");
+        let config = ler_yaml_ros(&escrever_yaml(&pasta, YAML)).unwrap();
+        assert_eq!(config.imagem, pasta.join("mapa.png"));
+        assert_eq!(config.resolucao, 0.05);
+        assert_eq!(config.origem, (-1.0, 2.5, 0.0));
+        assert_eq!((config.limiar_ocupado, config.limiar_livre, config.negar), (0.65, 0.196, false));
+    }
+
+    #[test]
+    fn yaml_invalido() {
+        let pasta = pasta_teste("invalido");
+        let trocar = |de: &str, para: &str| escrever_yaml(&pasta, &YAML.replace(de, para));
+        for (de, para) in [
+            ("resolution: 0.05", "resolution: -0.05"),
+            ("resolution: 0.05", "resolution: abc"),
+            ("origin: [-1.0, 2.5, 0.0]", "origin: [-1.0, 2.5]"),
+            ("free_thresh: 0.196", "free_thresh: 0.9"),
+            ("negate: 0", "negate: talvez"),
+            ("negate: 0", "mode: raw"),
+            ("image: \"mapa.png\"\n", ""),
+            ("negate: 0", "linha sem dois pontos"),
+        ] {
+            assert!(ler_yaml_ros(&trocar(de, para)).is_err(), "aceitou '{}'", para);
+        }
+        assert!(ler_yaml_ros(&pasta.join("nao_existe.yaml").to_string_lossy()).is_err());
+    }
+
+    #[test]
+    fn mapa_ros_com_tres_estados() {
+        let pasta = pasta_teste("estados");
+        // Coluna 0 preta (ocupada), coluna 1 cinza 205 (desconhecida), coluna 2 branca (livre)
+        let img = ImageBuffer::from_fn(3, 4, |x, _| Rgb([[0u8, 205, 255][x as usize]; 3]));
+        img.save(pasta.join("mapa.png")).unwrap();
+
+        let map = OccupancyMap::de_yaml_ros(&escrever_yaml(&pasta, YAML)).unwrap();
+        assert_eq!(map.estado_pixel(0, 0), EstadoCelula::Ocupado);
+        assert_eq!(map.estado_pixel(1, 0), EstadoCelula::Desconhecido);
+        assert_eq!(map.estado_pixel(2, 0), EstadoCelula::Livre);
+        let mundo = map.mundo.unwrap();
+        assert_eq!((mundo.resolucao, mundo.altura), (0.05, 4));
+    }
+
+    #[test]
+    fn transformacao_ida_e_volta() {
+        let t = TransformacaoMundo { resolucao: 0.05, origem: (-3.0, 1.5, 0.7), altura: 200 };
+        for p in [Point { x: 0.0, y: 0.0 }, Point { x: 123.4, y: 56.7 }, Point { x: 199.0, y: 199.0 }] {
+            let volta = t.para_pixel(&t.para_mundo(&p));
+            assert!(volta.dist(&p) < 1e-9);
+        }
+
+        // Sem rotação: o centro do pixel do canto inferior esquerdo fica meio pixel acima da origem
+        let reta = TransformacaoMundo { resolucao: 0.1, origem: (2.0, 3.0, 0.0), altura: 10 };
+        let canto = reta.para_mundo(&Point { x: 0.0, y: 9.0 });
+        assert!(canto.dist(&Point { x: 2.05, y: 3.05 }) < 1e-12);
+    }
+}
@@ -0,0 +1,244 @@
+
+//! #  Benchmarks do MovingAI (mapas `.map` e cenários `.scen`)
+//! Permite comparar os planejadores com os resultados publicados. O `.map` é uma grade ASCII:
+//! ```text
+//! type octile
+//! height 4
+//! width 6
+//! map
+//! @@@@@@
+//! @..T.@
+//! ...
+//! ```
+//! `.`, `G` e `S` são atravessáveis; `@`, `O`, `T` e `W` são obstáculo (água fica bloqueada, pois aqui não existe
+//! o terreno "só atravessável a partir da água"). O `.scen` tem uma consulta por linha
+//! (`bucket mapa largura altura x_inicio y_inicio x_fim y_fim comprimento_otimo`), com o comprimento ótimo
+//! octil sem cortar quinas, a mesma regra do `a_estrela_grade`.
+//! ## Output esperado
+//! Um `ResultadoCenario` por consulta, com o nosso comprimento e a razão em relação ao ótimo, que pode ser salvo
+//! em CSV com `salvar_resultados_csv`.
+
+#![allow(warnings)]
+
+use crate::classificador::EstadoCelula;
+use crate::readmap::OccupancyMap;
+use crate::structs::Point;
+use csv::Writer;
+use std::error::Error;
+use std::time::Instant;
+
+/// Lê o `.map` e retorna (largura, altura, estado de cada célula indexado por `y * largura + x`)
+pub fn ler_mapa_movingai(path: &str) -> Result<(u32, u32, Vec<EstadoCelula>), String> {
+    let texto = std::fs::read_to_string(path)
+        .map_err(|e| format!("Não foi possível ler o mapa '{}': {}", path, e))?;
+    let mut linhas = texto.lines();
+
+    // Cabeçalho: type, height, width (em qualquer ordem) até a linha "map"
+    let (mut largura, mut altura) = (None, None);
+    loop {
+        let linha = linhas.next().ok_or("Arquivo .map terminou antes da linha 'map'.")?.trim();
+        let mut partes = linha.split_whitespace();
+        match (partes.next(), partes.next()) {
+            (Some("map"), None) => break,
+            (Some("type"), Some(_)) => {}
+            (Some("height"), Some(v)) => altura = Some(v.parse::<u32>().map_err(|_| format!("Altura inválida: '{}'.", v))?),
+            (Some("width"), Some(v)) => largura = Some(v.parse::<u32>().map_err(|_| format!("Largura inválida: '{}'.", v))?),
+            (None, _) => {}
+            _ => return Err(format!("Linha de cabeçalho inesperada no .map: '{}'.", linha)),
+        }
+    }
+    let largura = largura.ok_or("Cabeçalho do .map sem 'width'.")?;
+    let altura = altura.ok_or("Cabeçalho do .map sem 'height'.")?;
+    if largura == 0 || altura == 0 {
+        return Err("O mapa não pode ser vazio.".to_string());
+    }
+
+    let mut estados = Vec::with_capacity((largura * altura) as usize);
+    for y in 0..altura {
+        let linha = linhas.next().ok_or_else(|| format!("O .map tem só {} linhas, esperava {}.", y, altura))?;
+        let linha = linha.trim_end_matches('\r');
+        if linha.chars().count() != largura as usize {
+            return Err(format!("A linha {} do .map tem {} células, esperava {}.", y, linha.chars().count(), largura));
+        }
+        for c in linha.chars() {
+            estados.push(match c {
+                '.' | 'G' | 'S' => EstadoCelula::Livre,
+                '@' | 'O' | 'T' | 'W' => EstadoCelula::Ocupado,
+                outro => return Err(format!("Célula desconhecida '{}' na linha {} do .map.", outro, y)),
+            });
+        }
+    }
+
+    Ok((largura, altura, estados))
+}
+
+/// Uma consulta do `.scen`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cenario {
+    pub bucket: u32,
+    pub mapa: String,
+    pub largura: u32,
+    pub altura: u32,
+    pub inicio: Point,
+    pub fim: Point,
+    pub comprimento_otimo: f64,
+}
+
+/// Lê um `.scen` (versão 1): a primeira linha é "version ..." e cada linha seguinte é um cenário
+pub fn ler_cenarios(path: &str) -> Result<Vec<Cenario>, String> {
+    let texto = std::fs::read_to_string(path)
+        .map_err(|e| format!("Não foi possível ler os cenários '{}': {}", path, e))?;
+
+    let mut cenarios = Vec::new();
+    for (n, linha) in texto.lines().enumerate() {
+        let linha = linha.trim();
+        if linha.is_empty() || linha.starts_with("version") {
+            continue;
+        }
+        let partes: Vec<&str> = linha.split_whitespace().collect();
+        if partes.len() != 9 {
+            return Err(format!("Linha {} do .scen tem {} campos, esperava 9.", n + 1, partes.len()));
+        }
+        let erro = |campo: &str| format!("Campo '{}' inválido na linha {} do .scen.", campo, n + 1);
+        let inteiro = |i: usize, campo: &str| partes[i].parse::<u32>().map_err(|_| erro(campo));
+
+        cenarios.push(Cenario {
+            bucket: inteiro(0, "bucket")?,
+            mapa: partes[1].to_string(),
+            largura: inteiro(2, "largura")?,
+            altura: inteiro(3, "altura")?,
+            inicio: Point { x: inteiro(4, "x_inicio")? as f64, y: inteiro(5, "y_inicio")? as f64 },
+            fim: Point { x: inteiro(6, "x_fim")? as f64, y: inteiro(7, "y_fim")? as f64 },
+            comprimento_otimo: partes[8].parse::<f64>().ok().filter(|v| v.is_finite()).ok_or_else(|| erro("comprimento_otimo"))?,
+        });
+    }
+    Ok(cenarios)
+}
+
+/// Resultado de um cenário
+#[derive(Debug, Clone)]
+pub struct ResultadoCenario {
+    pub cenario: Cenario,
+    /// Comprimento do caminho achado (None se o planejador falhou)
+    pub comprimento: Option<f64>,
+    /// comprimento / comprimento_otimo (1 é ótimo; menor que 1 só com caminhos de qualquer ângulo)
+    pub razao: Option<f64>,
+    pub tempo_ms: f64,
+}
+
+/// Roda cada cenário no planejador dado, que recebe o mapa, o início e o destino e devolve o caminho como
+/// pontos (ou None). Ex.: `|m, a, b| a_estrela_grade(m, a, b).ok().map(|r| r.caminho)`.
+/// Falha se as dimensões de algum cenário não baterem com as do mapa.
+pub fn executar_cenarios<F>(map: &OccupancyMap, cenarios: &[Cenario], mut planejador: F) -> Result<Vec<ResultadoCenario>, String>
+where
+    F: FnMut(&OccupancyMap, Point, Point) -> Option<Vec<Point>>,
+{
+    let (largura, altura) = map.image.dimensions();
+    let mut resultados = Vec::with_capacity(cenarios.len());
+
+    for cenario in cenarios {
+        if (cenario.largura, cenario.altura) != (largura, altura) {
+            return Err(format!(
+                "O cenário é para um mapa {}x{}, mas o mapa carregado é {}x{}.",
+                cenario.largura, cenario.altura, largura, altura
+            ));
+        }
+
+        let relogio = Instant::now();
+        let caminho = planejador(map, cenario.inicio, cenario.fim);
+        let tempo_ms = relogio.elapsed().as_secs_f64() * 1000.0;
+
+        let comprimento = caminho.map(|c| c.windows(2).map(|par| par[0].dist(&par[1])).sum::<f64>());
+        let razao = comprimento.map(|c| if cenario.comprimento_otimo > 0.0 { c / cenario.comprimento_otimo } else { 1.0 });
+        resultados.push(ResultadoCenario { cenario: cenario.clone(), comprimento, razao, tempo_ms });
+    }
+    Ok(resultados)
+}
+
+/// Salva os resultados em CSV. Cenários sem caminho ficam com comprimento e razão vazios.
+pub fn salvar_resultados_csv(resultados: &[ResultadoCenario], filename: &str) -> Result<(), Box<dyn Error>> {
+    let mut wtr = Writer::from_path(filename)?;
+    wtr.write_record(&["bucket", "x_inicio", "y_inicio", "x_fim", "y_fim", "otimo", "comprimento", "razao", "tempo_ms"])?;
+
+    for r in resultados {
+        let c = &r.cenario;
+        wtr.write_record(&[
+            c.bucket.to_string(),
+            c.inicio.x.to_string(),
+            c.inicio.y.to_string(),
+            c.fim.x.to_string(),
+            c.fim.y.to_string(),
+            c.comprimento_otimo.to_string(),
+            r.comprimento.map(|v| v.to_string()).unwrap_or_default(),
+            r.razao.map(|v| v.to_string()).unwrap_or_default(),
+            r.tempo_ms.to_string(),
+        ])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grade::a_estrela_grade;
+    use std::path::PathBuf;
+
+    /// Escreve o arquivo numa pasta temporária só deste teste (os testes rodam em paralelo)
+    fn escrever(teste: &str, nome: &str, conteudo: &str) -> String {
+        let pasta = std::env::temp_dir().join(format!("prm_movingai_{}_{}", teste, std::process::id()));
+        std::fs::create_dir_all(&pasta).unwrap();
+        let caminho: PathBuf = pasta.join(nome);
+        std::fs::write(&caminho, conteudo).unwrap();
+        caminho.to_string_lossy().into_owned()
+    }
+
+    const MAPA: &str = "type octile\nheight 5\nwidth 6\nmap\n@@@@@@\n@..T.@\n@.@@.@\n@G..S@\n@@@@@@\n";
+
+    #[test]
+    fn le_o_mapa() {
+        let (largura, altura, estados) = ler_mapa_movingai(&escrever("mapa", "teste.map", MAPA)).unwrap();
+        assert_eq!((largura, altura), (6, 5));
+        assert_eq!(estados[6 + 1], EstadoCelula::Livre);
+        assert_eq!(estados[6 + 3], EstadoCelula::Ocupado, "árvore é obstáculo");
+        assert_eq!(estados[3 * 6 + 1], EstadoCelula::Livre, "G é atravessável");
+        assert_eq!(estados.iter().filter(|&&e| e == EstadoCelula::Livre).count(), 9);
+    }
+
+    #[test]
+    fn mapa_invalido() {
+        for (de, para) in [("height 5", "height x"), ("width 6\n", ""), ("@G..S@", "@G..S"), ("@G..S@", "@G.?S@"), ("@@@@@@\n", "")] {
+            let caminho = escrever("invalido", "teste.map", &MAPA.replacen(de, para, 1));
+            assert!(ler_mapa_movingai(&caminho).is_err(), "aceitou '{}' -> '{}'", de, para);
+        }
+    }
+
+    #[test]
+    fn le_cenarios_e_executa() {
+        let scen = "version 1\n\
+            0\tteste.map\t6\t5\t1\t1\t4\t3\t5.00000000\n\
+            1\tteste.map\t6\t5\t1\t1\t1\t3\t2.00000000\n";
+        let cenarios = ler_cenarios(&escrever("cenarios", "teste.scen", scen)).unwrap();
+        assert_eq!(cenarios.len(), 2);
+        assert_eq!(cenarios[0].inicio.x, 1.0);
+        assert_eq!(cenarios[0].fim.y, 3.0);
+        assert_eq!(cenarios[1].comprimento_otimo, 2.0);
+
+        let map = OccupancyMap::de_movingai(&escrever("cenarios", "teste.map", MAPA)).unwrap();
+        let resultados = executar_cenarios(&map, &cenarios, |m, a, b| a_estrela_grade(m, a, b).ok().map(|r| r.caminho)).unwrap();
+        for r in &resultados {
+            assert!((r.razao.unwrap() - 1.0).abs() < 1e-6, "{:?}", r);
+        }
+    }
+
+    #[test]
+    fn cenarios_invalidos() {
+        assert!(ler_cenarios(&escrever("scen_invalido", "a.scen", "version 1\n0 teste.map 6 5 1 1 4\n")).is_err());
+        assert!(ler_cenarios(&escrever("scen_invalido", "b.scen", "version 1\n0 teste.map 6 5 1 -1 4 3 4.4\n")).is_err());
+
+        // Cenário de outro tamanho de mapa
+        let map = OccupancyMap::de_movingai(&escrever("scen_invalido", "teste.map", MAPA)).unwrap();
+        let cenarios = ler_cenarios(&escrever("scen_invalido", "c.scen", "version 1\n0 outro.map 10 10 1 1 4 3 4.4\n")).unwrap();
+        assert!(executar_cenarios(&map, &cenarios, |_, _, _| None).is_err());
+    }
+}
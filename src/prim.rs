@@ -0,0 +1,99 @@
+
+//! #  Algoritmo de Prim para a árvore geradora mínima
+//! Alternativa ao Kruskal que percorre diretamente a lista de adjacência (`Graph::adj`) com uma heap,
+//! sem precisar montar e ordenar a lista completa de arestas. Mais indicado para grafos densos.
+
+#![allow(warnings)]
+
+use crate::structs::{Graph, Peso};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Item da heap: uma aresta candidata a entrar na árvore
+#[derive(Debug, Clone, Copy)]
+struct Candidata {
+    /// O peso da aresta
+    weight: Peso,
+    /// O vértice que já está na árvore
+    from: usize,
+    /// O vértice que ainda não está na árvore
+    to: usize,
+}
+
+impl PartialEq for Candidata {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidata {}
+
+impl Ord for Candidata {
+    /// Ordem invertida (BinaryHeap é de máximo), então a menor aresta sai primeiro.
+    /// Empates seguem o par (menor índice, maior índice), a mesma ordem do `UndirEdge`, então a árvore é igual à do `kruskal`
+    fn cmp(&self, other: &Self) -> Ordering {
+        let par = |c: &Candidata| (c.from.min(c.to), c.from.max(c.to));
+        other.weight.cmp(&self.weight)
+            .then_with(|| par(other).cmp(&par(self)))
+    }
+}
+
+impl PartialOrd for Candidata {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Retorna uma Árvore Geradora Minima usando o algoritmo de Prim.
+/// Mesmo contrato do `kruskal`: a árvore recebe todos os vértices do grafo original e,
+/// se o grafo não for conexo, retorna um erro com a quantidade de componentes.
+pub fn prim(gr: &Graph) -> Result<Graph, String> {
+    let n = gr.vertices.len();
+
+    /// Inicializa a Árvore Geradora Minima com TODOS os vértices do grafo original
+    let mut agm = Graph::new();
+    for &v in &gr.vertices {
+        agm.add_vertex(v);
+    }
+
+    /// Marca os vértices que já entraram na árvore
+    let mut na_arvore = vec![false; n];
+    let mut heap = BinaryHeap::new();
+    let mut componentes = 0;
+
+    /// Cada vértice ainda fora da árvore inicia uma nova componente (só acontece mais de uma vez se o grafo for desconexo)
+    for raiz in 0..n {
+        if na_arvore[raiz] {
+            continue;
+        }
+        componentes += 1;
+        na_arvore[raiz] = true;
+        for edge in &gr.adj[raiz] {
+            heap.push(Candidata { weight: edge.weight, from: raiz, to: edge.to_idx });
+        }
+
+        /// Retira sempre a aresta mais leve que sai da árvore atual
+        while let Some(Candidata { weight, from, to }) = heap.pop() {
+            /// Se o destino já está na árvore, a aresta formaria um ciclo
+            if na_arvore[to] {
+                continue;
+            }
+            na_arvore[to] = true;
+            agm.add_edge_peso(from, to, weight)?;
+
+            /// Adiciona as arestas do novo vértice que levam para fora da árvore
+            for edge in &gr.adj[to] {
+                if !na_arvore[edge.to_idx] {
+                    heap.push(Candidata { weight: edge.weight, from: to, to: edge.to_idx });
+                }
+            }
+        }
+    }
+
+    // Se mais de uma raíz foi necessária, o grafo não é conexo
+    if componentes > 1 {
+        return Err(format!("O grafo não é conexo. Existem {} componentes.", componentes));
+    }
+
+    Ok(agm)
+}
@@ -5,14 +5,16 @@
 
 use crate::readmap::OccupancyMap;
 use crate::structs::{Graph, Point};
+use crate::amostragem::{Sampler, AmostradorUniforme};
 use rand::Rng;
 use std::error::Error;
 use csv::Writer;
+use std::collections::{HashMap, HashSet};
 
 // --- Parte 1: Geração do Grafo Aleatório (PRM) ---
 
 /// Gera um ponto aleatório VÁLIDO (não obstruído) no mapa.
-fn sample_valid_point(map: &OccupancyMap, mut rng: &mut impl Rng) -> Point {
+pub fn sample_valid_point(map: &OccupancyMap, mut rng: &mut impl Rng) -> Point {
     let (x_min, y_min, x_max, y_max) = map.pixel_bounds;
     loop {
         let x = rng.gen_range(x_min as f64..=x_max as f64);
@@ -27,21 +29,145 @@ fn sample_valid_point(map: &OccupancyMap, mut rng: &mut impl Rng) -> Point {
     }
 }
 
+/// Define como o PRM escolhe quais pares de vértices tenta conectar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModoConexao {
+    /// Tenta conectar todos os pares a até essa distância (em pixels). É o modo original.
+    Raio(f64),
+    /// kPRM: conecta cada vértice aos seus `k` vizinhos mais próximos com caminho livre.
+    /// Com `mutuo = false` a aresta entra se um estiver entre os k do outro (simétrico);
+    /// com `mutuo = true` só entra se cada um estiver entre os k do outro (k mútuo).
+    KVizinhos { k: usize, mutuo: bool },
+    /// PRM*: o raio é calculado pelo número de amostras e pela área livre do mapa (ver `raio_prm_estrela`).
+    RaioPrmEstrela,
+    /// kPRM*: o k é calculado pelo número de amostras (ver `k_prm_estrela`).
+    KPrmEstrela { mutuo: bool },
+}
+
+impl ModoConexao {
+    /// Troca os modos PRM* pelo raio/k concreto para esse mapa e número de vértices.
+    /// Os outros modos são retornados sem mudança.
+    pub fn resolver(self, map: &OccupancyMap, num_vertices: usize) -> ModoConexao {
+        match self {
+            ModoConexao::RaioPrmEstrela => ModoConexao::Raio(raio_prm_estrela(map, num_vertices)),
+            ModoConexao::KPrmEstrela { mutuo } => ModoConexao::KVizinhos { k: k_prm_estrela(num_vertices), mutuo },
+            outro => outro,
+        }
+    }
+
+    /// Maior comprimento de aresta que o modo pode gerar, se existir (usado na validação do grafo).
+    /// Para `RaioPrmEstrela` é preciso chamar `resolver` antes.
+    pub fn raio_maximo(&self) -> Option<f64> {
+        match self {
+            ModoConexao::Raio(r) => Some(*r),
+            _ => None,
+        }
+    }
+}
+
+/// Dimensão do espaço de configuração (x, y)
+const DIMENSAO: f64 = 2.0;
+/// Fator acima do mínimo teórico das constantes do PRM* (a teoria exige estritamente maior que o mínimo)
+const FATOR_PRM_ESTRELA: f64 = 1.1;
+
+/// Constante γ do PRM* para o mapa: γ > 2 (1 + 1/d)^(1/d) (área livre / volume da bola unitária)^(1/d).
+/// No plano d = 2 e a bola unitária tem área π. A mesma constante serve para o raio do RRT*.
+pub fn gamma_prm_estrela(map: &OccupancyMap) -> f64 {
+    let gamma_min = 2.0 * (1.0 + 1.0 / DIMENSAO).powf(1.0 / DIMENSAO)
+        * (map.area_livre() / std::f64::consts::PI).powf(1.0 / DIMENSAO);
+    FATOR_PRM_ESTRELA * gamma_min
+}
+
+/// Raio para `num_vertices` amostras dado o γ: r(n) = γ (ln n / n)^(1/d)
+pub fn raio_estrela(gamma: f64, num_vertices: usize) -> f64 {
+    let n = num_vertices.max(2) as f64;
+    gamma * (n.ln() / n).powf(1.0 / DIMENSAO)
+}
+
+/// Raio do PRM* para `num_vertices` amostras (ver `gamma_prm_estrela` e `raio_estrela`)
+pub fn raio_prm_estrela(map: &OccupancyMap, num_vertices: usize) -> f64 {
+    raio_estrela(gamma_prm_estrela(map), num_vertices)
+}
+
+/// k do kPRM* para `num_vertices` amostras: k(n) = ⌈k_PRM ln n⌉, com k_PRM > e (1 + 1/d).
+pub fn k_prm_estrela(num_vertices: usize) -> usize {
+    let n = num_vertices.max(2) as f64;
+    let k_prm = FATOR_PRM_ESTRELA * std::f64::consts::E * (1.0 + 1.0 / DIMENSAO);
+    (k_prm * n.ln()).ceil() as usize
+}
+
+/// Quantidade de verificações de colisão para um segmento desse comprimento (uma a cada 2 pixels)
+pub fn passos_colisao(dist: f64) -> i32 {
+    (dist / 2.0).ceil().max(1.0) as i32
+}
+
 /// Gera um grafo aleatório (PRM) no mapa.
 pub fn generate_random_graph(map: &OccupancyMap, num_vertices: usize, connection_radius: f64) -> Graph {
+    generate_prm(map, num_vertices, ModoConexao::Raio(connection_radius))
+}
+
+/// Gera um grafo aleatório (PRM) no mapa, conectando os vértices segundo o `modo` escolhido.
+pub fn generate_prm(map: &OccupancyMap, num_vertices: usize, modo: ModoConexao) -> Graph {
+    gerar(map, num_vertices, modo, &mut AmostradorUniforme, true)
+}
+
+/// Gera um grafo aleatório (PRM) no mapa usando a estratégia de amostragem `amostrador`
+/// (por exemplo um `AmostradorMisto` com uniforme + ponte para cobrir as portas).
+/// Se o amostrador falhar muitas vezes seguidas, o grafo pode ficar com menos de `num_vertices` vértices.
+pub fn generate_prm_com_amostrador(map: &OccupancyMap, num_vertices: usize, modo: ModoConexao, amostrador: &mut dyn Sampler) -> Graph {
+    gerar(map, num_vertices, modo, amostrador, true)
+}
+
+/// Gera o grafo do Lazy PRM: igual ao `generate_prm`, mas as arestas entram SEM checagem de colisão.
+/// As arestas são conferidas só na hora da consulta (ver `lazy_prm`).
+pub fn generate_prm_lazy(map: &OccupancyMap, num_vertices: usize, modo: ModoConexao) -> Graph {
+    gerar(map, num_vertices, modo, &mut AmostradorUniforme, false)
+}
+
+/// Quantas tentativas sem sucesso do amostrador são aceitas por vértice antes de desistir
+pub(crate) const TENTATIVAS_POR_VERTICE: usize = 10_000;
+
+/// Amostra os vértices e conecta segundo o `modo`, checando colisão das arestas só se `verificar_colisao`
+fn gerar(map: &OccupancyMap, num_vertices: usize, modo: ModoConexao, amostrador: &mut dyn Sampler, verificar_colisao: bool) -> Graph {
     let mut graph = Graph::new();
     let mut rng = rand::thread_rng();
 
     // 1. Fase de Amostragem: Adiciona N vértices válidos
-    for _ in 0..num_vertices {
-        let point = sample_valid_point(map, &mut rng);
-        graph.add_vertex(point);
+    let mut tentativas = 0;
+    while graph.vertices.len() < num_vertices && tentativas < num_vertices * TENTATIVAS_POR_VERTICE {
+        tentativas += 1;
+        if let Some(point) = amostrador.sample(map, &mut rng) {
+            graph.add_vertex(point);
+        }
     }
-    
+
+    // 2. Fase de Conexão
+    match modo.resolver(map, num_vertices) {
+        ModoConexao::Raio(connection_radius) => conectar_por_raio(&mut graph, map, connection_radius, verificar_colisao),
+        ModoConexao::KVizinhos { k, mutuo } => conectar_k_vizinhos(&mut graph, map, k, mutuo, verificar_colisao),
+        _ => unreachable!("resolver sempre retorna Raio ou KVizinhos"),
+    }
+    graph
+}
+
+/// Peso de uma aresta já aceita: o `custo_segmento` do mapa (igual ao comprimento em espaço livre, maior quando
+/// atravessa células desconhecidas com custo). Sem checagem de colisão (Lazy PRM) usa só o comprimento,
+/// pois o segmento pode atravessar obstáculos.
+fn peso_aresta(map: &OccupancyMap, p1: &Point, p2: &Point, passos: i32, verificar_colisao: bool) -> f64 {
+    if verificar_colisao {
+        map.custo_segmento(p1, p2, passos)
+    } else {
+        p1.dist(p2)
+    }
+}
+
+/// Conecta todos os pares de vértices a até `connection_radius` com caminho livre
+/// (ou todos os pares no raio, se `verificar_colisao` for false).
+fn conectar_por_raio(graph: &mut Graph, map: &OccupancyMap, connection_radius: f64, verificar_colisao: bool) {
     // Define a quantidade de verificações de colisão por segmento
-    let collision_check_steps = (connection_radius / 2.0).ceil().max(1.0) as i32;
+    let collision_check_steps = passos_colisao(connection_radius);
 
-    // 2. Fase de Conexão: Tenta conectar vértices próximos
+    // Tenta conectar vértices próximos
     let vertices = graph.vertices.clone(); // Clona para evitar problemas de empréstimo
     for i in 0..vertices.len() {
         for j in (i + 1)..vertices.len() {
@@ -53,15 +179,70 @@ pub fn generate_random_graph(map: &OccupancyMap, num_vertices: usize, connection
             // Se estiverem dentro do raio de conexão
             if dist <= connection_radius {
                 // E se o caminho entre eles for livre
-                if !map.is_path_colliding(&p1, &p2, collision_check_steps) {
+                if !verificar_colisao || !map.is_path_colliding(&p1, &p2, collision_check_steps) {
                     // Adiciona a aresta não-direcionada (em ambas as direções)
-                    graph.add_edge(i, j, dist);
+                    let peso = peso_aresta(map, &p1, &p2, collision_check_steps, verificar_colisao);
+                    // Um custo infinito (multiplicador de desconhecido enorme) não vira aresta
+                    if peso.is_finite() {
+                        graph.add_edge(i, j, peso).expect("Os índices são válidos e o peso é finito.");
+                    }
                     //graph.add_edge(j, i, dist); comentado pq add_edge ja faz isso
                 }
             }
         }
     }
-    graph
+}
+
+/// kPRM: conecta cada vértice aos seus `k` vizinhos mais próximos com caminho livre
+/// (ou aos `k` mais próximos sem olhar obstáculos, se `verificar_colisao` for false).
+fn conectar_k_vizinhos(graph: &mut Graph, map: &OccupancyMap, k: usize, mutuo: bool, verificar_colisao: bool) {
+    let vertices = graph.vertices.clone();
+    let n = vertices.len();
+
+    // Guarda o resultado da checagem de colisão de cada par, já que o par é visto pelos dois lados
+    let mut livre: HashMap<(usize, usize), bool> = HashMap::new();
+    // Para cada vértice, os k vizinhos mais próximos alcançáveis em linha reta
+    let mut vizinhos: Vec<HashSet<usize>> = vec![HashSet::new(); n];
+
+    for i in 0..n {
+        // Ordena os outros vértices por distância (empate pelo índice, para ser determinístico)
+        let mut candidatos: Vec<(f64, usize)> = (0..n)
+            .filter(|&j| j != i)
+            .map(|j| (vertices[i].dist(&vertices[j]), j))
+            .collect();
+        candidatos.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+        // Percorre do mais próximo para o mais distante até achar k vizinhos com caminho livre
+        for (dist, j) in candidatos {
+            if vizinhos[i].len() >= k {
+                break;
+            }
+            let par = (i.min(j), i.max(j));
+            let eh_livre = *livre
+                .entry(par)
+                .or_insert_with(|| !verificar_colisao || !map.is_path_colliding(&vertices[i], &vertices[j], passos_colisao(dist)));
+            if eh_livre {
+                vizinhos[i].insert(j);
+            }
+        }
+    }
+
+    // Escolhe as arestas: simétrico (um lado basta) ou mútuo (os dois lados)
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let i_escolheu_j = vizinhos[i].contains(&j);
+            let j_escolheu_i = vizinhos[j].contains(&i);
+            let conecta = if mutuo { i_escolheu_j && j_escolheu_i } else { i_escolheu_j || j_escolheu_i };
+
+            if conecta {
+                let passos = passos_colisao(vertices[i].dist(&vertices[j]));
+                let peso = peso_aresta(map, &vertices[i], &vertices[j], passos, verificar_colisao);
+                if peso.is_finite() {
+                    graph.add_edge(i, j, peso).expect("Os índices são válidos e o peso é finito.");
+                }
+            }
+        }
+    }
 }
 
 
@@ -106,3 +287,91 @@ pub fn save_graph_to_csv(graph: &Graph, file_path: &str) -> Result<(), Box<dyn E
     wtr.flush()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readmap::mapa_de_teste;
+    use crate::validacao::{validate, Violacao};
+
+    /// Sala 60x40 só com a moldura: todo par de vértices se enxerga
+    fn sala_vazia() -> OccupancyMap {
+        mapa_de_teste(60, 40, |x, y| x == 0 || y == 0 || x == 59 || y == 39)
+    }
+
+    fn graus(gr: &Graph) -> Vec<usize> {
+        gr.adj.iter().map(|vizinhos| vizinhos.len()).collect()
+    }
+
+    #[test]
+    fn k_vizinhos_simetrico_tem_grau_pelo_menos_k() {
+        let gr = generate_prm(&sala_vazia(), 80, ModoConexao::KVizinhos { k: 6, mutuo: false });
+        assert!(graus(&gr).iter().all(|&grau| grau >= 6), "{:?}", graus(&gr));
+        assert_eq!(validate(&gr, None), Ok(()));
+    }
+
+    #[test]
+    fn k_vizinhos_mutuo_tem_grau_no_maximo_k() {
+        let gr = generate_prm(&sala_vazia(), 80, ModoConexao::KVizinhos { k: 6, mutuo: true });
+        assert!(graus(&gr).iter().all(|&grau| grau <= 6), "{:?}", graus(&gr));
+        assert!(graus(&gr).iter().any(|&grau| grau > 0));
+        assert_eq!(validate(&gr, None), Ok(()));
+    }
+
+    #[test]
+    fn raio_conecta_todos_os_pares_proximos() {
+        let raio = 10.0;
+        let gr = generate_prm(&sala_vazia(), 100, ModoConexao::Raio(raio));
+        for i in 0..gr.vertices.len() {
+            let dentro_do_raio = (0..gr.vertices.len()).filter(|&j| j != i && gr.vertices[i].dist(&gr.vertices[j]) <= raio).count();
+            assert_eq!(gr.adj[i].len(), dentro_do_raio);
+        }
+    }
+
+    #[test]
+    fn raio_maximo_alimenta_a_validacao() {
+        let modo = ModoConexao::Raio(10.0);
+        let gr = generate_prm(&sala_vazia(), 150, modo);
+        assert_eq!(modo.raio_maximo(), Some(10.0));
+        assert_eq!(validate(&gr, modo.raio_maximo()), Ok(()));
+
+        // Com um raio menor que o usado na geração, as arestas compridas são apontadas
+        let violacoes = validate(&gr, Some(5.0)).unwrap_err();
+        assert!(violacoes.iter().all(|v| matches!(v, Violacao::ArestaMaiorQueRaio { raio, .. } if *raio == 5.0)));
+        let compridas = gr.get_undirected_edges().iter().filter(|e| e.weight.valor() > 5.0).count();
+        assert_eq!(violacoes.len(), compridas);
+
+        assert_eq!(ModoConexao::KVizinhos { k: 6, mutuo: false }.raio_maximo(), None);
+    }
+
+    #[test]
+    fn raio_prm_estrela_segue_a_formula() {
+        let map = sala_vazia();
+        // Área livre 58 x 38 = 2204: r(n) = 1.1 * 2 * sqrt(1.5) * sqrt(2204 / π) * sqrt(ln n / n)
+        assert_eq!(map.area_livre(), 2204.0);
+        assert!((raio_prm_estrela(&map, 100) - 15.3152).abs() < 1e-3);
+        assert!((raio_prm_estrela(&map, 1000) - 5.9315).abs() < 1e-3);
+
+        let raios: Vec<f64> = [10, 100, 1000, 10_000].iter().map(|&n| raio_prm_estrela(&map, n)).collect();
+        assert!(raios.windows(2).all(|par| par[1] < par[0]), "o raio não diminui com n: {:?}", raios);
+
+        assert_eq!(k_prm_estrela(100), 21);
+        assert_eq!(k_prm_estrela(1000), 31);
+    }
+
+    #[test]
+    fn modos_prm_estrela_resolvem_para_raio_e_k() {
+        let map = sala_vazia();
+        let modo = ModoConexao::RaioPrmEstrela.resolver(&map, 100);
+        assert_eq!(modo, ModoConexao::Raio(raio_prm_estrela(&map, 100)));
+        assert_eq!(ModoConexao::RaioPrmEstrela.raio_maximo(), None);
+        assert_eq!(
+            ModoConexao::KPrmEstrela { mutuo: true }.resolver(&map, 100),
+            ModoConexao::KVizinhos { k: 21, mutuo: true }
+        );
+        assert_eq!(ModoConexao::Raio(3.0).resolver(&map, 100), ModoConexao::Raio(3.0));
+
+        let gr = generate_prm(&map, 100, ModoConexao::RaioPrmEstrela);
+        assert_eq!(validate(&gr, modo.raio_maximo()), Ok(()));
+    }
+}
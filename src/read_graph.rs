@@ -37,8 +37,8 @@ pub fn read_graph(file_path: &str) -> Result<Graph, Box<dyn Error>> {
         let u_idx = *point_to_idx.entry(p1).or_insert_with(|| graph.add_vertex(p1));
         let v_idx = *point_to_idx.entry(p2).or_insert_with(|| graph.add_vertex(p2));
 
-        // Adiciona a aresta (bidirecional)
-        graph.add_edge(u_idx, v_idx, weight);
+        // Adiciona a aresta (bidirecional), recusando pesos NaN ou infinitos
+        graph.add_edge(u_idx, v_idx, weight)?;
     }
 
     // Retorna o grafo
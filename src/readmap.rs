@@ -6,7 +6,11 @@ use std::io::{self, BufRead};
 use std::path::Path;
 
 use crate::structs::*;
-pub use image::{ImageBuffer, Rgb};
+use crate::classificador::{ClassificadorOcupacao, EstadoCelula, ModoLimiar, PoliticaAlfa, PoliticaDesconhecido};
+use crate::mapa_ros::{ler_yaml_ros, TransformacaoMundo};
+use crate::movingai::ler_mapa_movingai;
+use crate::camada_custo::{conferir_custo, ler_camada_custo, FonteCusto};
+pub use image::{DynamicImage, ImageBuffer, Rgb};
 
 
 pub fn is_black(pixel: &Rgb<u8>) -> bool {
@@ -15,8 +19,13 @@ pub fn is_black(pixel: &Rgb<u8>) -> bool {
 }
 
 pub fn find_bounds(image: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> (u32, u32, u32, u32) {
-    
     let (width, height) = image.dimensions();
+    find_bounds_por(width, height, |x, y| is_black(image.get_pixel(x, y)))
+}
+
+/// Mesmo que `find_bounds`, mas com qualquer critério de obstáculo (ex.: a máscara de um `ClassificadorOcupacao`)
+pub fn find_bounds_por(width: u32, height: u32, ocupado: impl Fn(u32, u32) -> bool) -> (u32, u32, u32, u32) {
+
     let mut y_min = 0;
     let mut y_max = height - 1;
     let mut x_min = 0;
@@ -25,7 +34,7 @@ pub fn find_bounds(image: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> (u32, u32, u32, u32
     for y in 0..height {
         let mut found_non_black = false;
         for x in 0..width {
-            if !is_black(image.get_pixel(x, y)) {
+            if !ocupado(x, y) {
                 y_min = y;
                 found_non_black = true;
                 break;
@@ -37,7 +46,7 @@ pub fn find_bounds(image: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> (u32, u32, u32, u32
     for y in (0..height).rev() {
         let mut found_non_black = false;
         for x in 0..width {
-            if !is_black(image.get_pixel(x, y)) {
+            if !ocupado(x, y) {
                 y_max = y;
                 found_non_black = true;
                 break;
@@ -49,7 +58,7 @@ pub fn find_bounds(image: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> (u32, u32, u32, u32
     for x in 0..width {
         let mut found_non_black = false;
         for y in y_min..=y_max {
-            if !is_black(image.get_pixel(x, y)) {
+            if !ocupado(x, y) {
                 x_min = x;
                 found_non_black = true;
                 break;
@@ -61,7 +70,7 @@ pub fn find_bounds(image: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> (u32, u32, u32, u32
     for x in (0..width).rev() {
         let mut found_non_black = false;
         for y in y_min..=y_max {
-            if !is_black(image.get_pixel(x, y)) {
+            if !ocupado(x, y) {
                 x_max = x;
                 found_non_black = true;
                 break;
@@ -73,26 +82,164 @@ pub fn find_bounds(image: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> (u32, u32, u32, u32
     (x_min, y_min, x_max, y_max)
 }
 
+/// Abre a imagem do mapa, com o caminho na mensagem de erro
+fn abrir_imagem(file_path: &str) -> Result<DynamicImage, String> {
+    image::open(file_path).map_err(|e| format!("Não foi possível abrir a imagem do mapa '{}': {}", file_path, e))
+}
+
 pub struct OccupancyMap {
     pub image: ImageBuffer<Rgb<u8>, Vec<u8>>,
     pub pixel_bounds: (u32, u32, u32, u32), // (x_min, y_min, x_max, y_max)
+    /// Conversão pixel <-> metros, quando o mapa veio de um YAML do ROS
+    pub mundo: Option<TransformacaoMundo>,
+    /// Como os planejadores tratam células desconhecidas (padrão: `Proibir`). Só muda pelo
+    /// `definir_politica_desconhecido`, que confere o multiplicador de `PermitirComCusto`.
+    politica_desconhecido: PoliticaDesconhecido,
+    /// Estado de cada pixel segundo o classificador usado na leitura, indexado por `y * largura + x`
+    estados: Vec<EstadoCelula>,
+    /// Se o mapa foi restrito a uma região livre, os pixels dela (os demais contam como obstáculo)
+    alcancavel: Option<Vec<bool>>,
+    /// Camada de custo do terreno (multiplicador >= 1 por pixel), se carregada
+    custo_terreno: Option<Vec<f64>>,
+}
+
+/// Regiões livres conexas do mapa (vizinhança de 4, a mesma conectividade dos planejadores em grade sem cortar quinas)
+#[derive(Debug, Clone)]
+pub struct RegioesLivres {
+    largura: u32,
+    /// Região de cada pixel, indexado por `y * largura + x` (None para obstáculo)
+    rotulos: Vec<Option<usize>>,
+    /// Quantidade de pixels de cada região
+    pub tamanhos: Vec<usize>,
+}
+
+impl RegioesLivres {
+    /// Região do pixel (x, y), ou None se ele não for livre
+    pub fn regiao_do_pixel(&self, x: u32, y: u32) -> Option<usize> {
+        self.rotulos[(y * self.largura + x) as usize]
+    }
+
+    /// Índice da maior região (empate pela de menor índice)
+    pub fn maior(&self) -> Option<usize> {
+        (0..self.tamanhos.len()).max_by(|&a, &b| self.tamanhos[a].cmp(&self.tamanhos[b]).then_with(|| b.cmp(&a)))
+    }
 }
 
 impl OccupancyMap {
+    /// Lê o mapa da imagem e imprime os limites. Entra em pânico se a imagem não abrir; para tratar o erro
+    /// (e não imprimir nada), use `try_new`.
     pub fn new(file_path: &str) -> Self {
-        let img = image::open(file_path).expect("Não foi possível abrir a imagem do mapa.");
-        let rgb_img = img.to_rgb8();
+        let map = Self::try_new(file_path).expect("Não foi possível abrir a imagem do mapa.");
+        println!("Limites de navegação detectados (pixels): {:?}", map.pixel_bounds);
+        map
+    }
 
-        let pixel_bounds = find_bounds(&rgb_img);
-        println!("Limites de navegação detectados (pixels): {:?}", pixel_bounds);
+    /// Igual ao `new`, mas retorna o erro em vez de entrar em pânico e não imprime nada
+    pub fn try_new(file_path: &str) -> Result<Self, String> {
+        Self::de_imagem(abrir_imagem(file_path)?, &ClassificadorOcupacao::default())
+    }
+
+    /// Igual ao `try_new`, mas classificando os pixels com o classificador dado (limiar, Otsu, alfa, invertido)
+    pub fn com_classificador(file_path: &str, classificador: &ClassificadorOcupacao) -> Result<Self, String> {
+        Self::de_imagem(abrir_imagem(file_path)?, classificador)
+    }
+
+    /// Monta o mapa a partir de uma imagem RGB em memória, sem imprimir nada
+    pub fn de_buffer(img: ImageBuffer<Rgb<u8>, Vec<u8>>, classificador: &ClassificadorOcupacao) -> Result<Self, String> {
+        Self::de_imagem(DynamicImage::ImageRgb8(img), classificador)
+    }
+
+    /// Monta o mapa a partir dos bytes de um arquivo de imagem (PNG, JPEG, PGM...) em memória, sem imprimir nada
+    pub fn de_bytes(bytes: &[u8], classificador: &ClassificadorOcupacao) -> Result<Self, String> {
+        let img = image::load_from_memory(bytes).map_err(|e| format!("Não foi possível decodificar a imagem do mapa: {}", e))?;
+        Self::de_imagem(img, classificador)
+    }
+
+    /// Monta o mapa a partir de uma grade de booleanos (uma linha por `Vec`, true = obstáculo), sem imprimir nada.
+    /// Todas as linhas precisam ter o mesmo tamanho.
+    pub fn de_grade(linhas: &[Vec<bool>]) -> Result<Self, String> {
+        let altura = linhas.len();
+        let largura = linhas.first().map(|l| l.len()).unwrap_or(0);
+        if let Some(y) = linhas.iter().position(|l| l.len() != largura) {
+            return Err(format!("A linha {} da grade tem {} células, esperava {}.", y, linhas[y].len(), largura));
+        }
+
+        let estados = linhas
+            .iter()
+            .flatten()
+            .map(|&ocupado| if ocupado { EstadoCelula::Ocupado } else { EstadoCelula::Livre })
+            .collect();
+        Self::de_estados(largura as u32, altura as u32, estados)
+    }
+
+    /// Lê um mapa do map_server do ROS (YAML + imagem), sem imprimir nada. Os pixels são classificados pelos limiares de
+    /// probabilidade do YAML (livre, ocupado ou desconhecido) e o mapa guarda a transformação para metros em `mundo`.
+    pub fn de_yaml_ros(yaml_path: &str) -> Result<Self, String> {
+        let config = ler_yaml_ros(yaml_path)?;
+        let img = abrir_imagem(&config.imagem.to_string_lossy())?;
+
+        let classificador = ClassificadorOcupacao {
+            modo: ModoLimiar::Probabilidade { ocupado: config.limiar_ocupado, livre: config.limiar_livre },
+            alfa: PoliticaAlfa::Ignorar,
+            invertido: config.negar,
+            desconhecido: None,
+        };
+        let mut map = Self::de_imagem(img, &classificador)?;
+        map.mundo = Some(TransformacaoMundo {
+            resolucao: config.resolucao,
+            origem: config.origem,
+            altura: map.image.height(),
+        });
+        Ok(map)
+    }
+
+    /// Lê um mapa de grade do MovingAI (`.map`), sem imprimir nada. Cada célula vira um pixel.
+    pub fn de_movingai(path: &str) -> Result<Self, String> {
+        let (largura, altura, estados) = ler_mapa_movingai(path)?;
+        Self::de_estados(largura, altura, estados)
+    }
+
+    /// Monta o mapa a partir de uma imagem já carregada.
+    /// Os limites de navegação cortam só as bordas de obstáculo; células desconhecidas ficam dentro deles.
+    fn de_imagem(img: DynamicImage, classificador: &ClassificadorOcupacao) -> Result<Self, String> {
+        let estados = classificador.classificar_estados(&img);
+        Self::montar(img.to_rgb8(), estados)
+    }
+
+    /// Monta o mapa a partir do estado de cada célula, desenhando a imagem
+    /// (livre = branco, obstáculo = preto, desconhecido = cinza 205, como nos mapas do ROS)
+    pub(crate) fn de_estados(largura: u32, altura: u32, estados: Vec<EstadoCelula>) -> Result<Self, String> {
+        if estados.len() != (largura as usize) * (altura as usize) {
+            return Err(format!("Esperava {} células para um mapa {}x{}, vieram {}.", largura * altura, largura, altura, estados.len()));
+        }
+        let rgb_img = ImageBuffer::from_fn(largura, altura, |x, y| match estados[(y * largura + x) as usize] {
+            EstadoCelula::Livre => Rgb([255, 255, 255]),
+            EstadoCelula::Ocupado => Rgb([0, 0, 0]),
+            EstadoCelula::Desconhecido => Rgb([205, 205, 205]),
+        });
+        Self::montar(rgb_img, estados)
+    }
+
+    /// Todos os construtores passam por aqui: recusa imagens vazias e calcula os limites de navegação
+    fn montar(rgb_img: ImageBuffer<Rgb<u8>, Vec<u8>>, estados: Vec<EstadoCelula>) -> Result<Self, String> {
+        let (largura, altura) = rgb_img.dimensions();
+        if largura == 0 || altura == 0 {
+            return Err(format!("A imagem do mapa está vazia ({}x{}).", largura, altura));
+        }
+        let pixel_bounds = find_bounds_por(largura, altura, |x, y| estados[(y * largura + x) as usize] == EstadoCelula::Ocupado);
 
-        OccupancyMap {
+        Ok(OccupancyMap {
             image: rgb_img,
             pixel_bounds,
-        }
+            politica_desconhecido: PoliticaDesconhecido::Proibir,
+            mundo: None,
+            estados,
+            alcancavel: None,
+            custo_terreno: None,
+        })
     }
 
-    // Verifica se um ponto específico está em um obstáculo (pixel preto)
+    // Verifica se um ponto específico está em um obstáculo (pixel classificado como obstáculo)
     pub fn is_obstructed(&self, point: &Point) -> bool {
         let (x_min_px, y_min_px, x_max_px, y_max_px) = self.pixel_bounds;
         
@@ -106,8 +253,267 @@ impl OccupancyMap {
             return true; 
         }
 
-        let pixel = self.image.get_pixel(px, py);
-        is_black(pixel)
+        self.pixel_ocupado(px, py)
+    }
+
+    /// Retorna true se o pixel (x, y) não pode ser atravessado: obstáculo, desconhecido com a política `Proibir`
+    /// ou fora da região escolhida com `restringir_a_regiao`.
+    /// O pixel precisa estar dentro da imagem.
+    pub fn pixel_ocupado(&self, x: u32, y: u32) -> bool {
+        if !self.pixel_alcancavel(x, y) {
+            return true;
+        }
+        self.ocupado_sem_restricao(x, y)
+    }
+
+    /// Se o mapa foi restrito com `restringir_a_regiao`, diz se o pixel está na região; senão, sempre true
+    pub fn pixel_alcancavel(&self, x: u32, y: u32) -> bool {
+        match &self.alcancavel {
+            Some(mascara) => mascara[(y * self.image.width() + x) as usize],
+            None => true,
+        }
+    }
+
+    /// `pixel_ocupado` ignorando a restrição de região
+    fn ocupado_sem_restricao(&self, x: u32, y: u32) -> bool {
+        match self.estado_pixel(x, y) {
+            EstadoCelula::Livre => false,
+            EstadoCelula::Ocupado => true,
+            EstadoCelula::Desconhecido => self.politica_desconhecido == PoliticaDesconhecido::Proibir,
+        }
+    }
+
+    /// Estado do pixel (x, y) como foi classificado, sem aplicar a política. O pixel precisa estar dentro da imagem.
+    pub fn estado_pixel(&self, x: u32, y: u32) -> EstadoCelula {
+        self.estados[(y * self.image.width() + x) as usize]
+    }
+
+    /// Como os planejadores tratam as células desconhecidas
+    pub fn politica_desconhecido(&self) -> PoliticaDesconhecido {
+        self.politica_desconhecido
+    }
+
+    /// Muda a política das células desconhecidas. Com `PermitirComCusto`, o multiplicador precisa ser finito
+    /// e pelo menos 1 (como os custos da camada de terreno).
+    pub fn definir_politica_desconhecido(&mut self, politica: PoliticaDesconhecido) -> Result<(), String> {
+        if let PoliticaDesconhecido::PermitirComCusto(c) = politica {
+            conferir_custo(c, "das células desconhecidas")?;
+        }
+        self.politica_desconhecido = politica;
+        Ok(())
+    }
+
+    /// Custo por unidade de comprimento para atravessar o pixel (x, y): 1 se livre, infinito se não puder ser
+    /// atravessado e o multiplicador da política `PermitirComCusto` se for desconhecido. Com uma camada de custo
+    /// carregada, o valor ainda é multiplicado pelo custo do terreno no pixel.
+    pub fn custo_pixel(&self, x: u32, y: u32) -> f64 {
+        if !self.pixel_alcancavel(x, y) {
+            return f64::INFINITY;
+        }
+        let base = match (self.estado_pixel(x, y), self.politica_desconhecido) {
+            (EstadoCelula::Livre, _) | (EstadoCelula::Desconhecido, PoliticaDesconhecido::Otimista) => 1.0,
+            (EstadoCelula::Desconhecido, PoliticaDesconhecido::PermitirComCusto(c)) => c,
+            _ => return f64::INFINITY,
+        };
+        match &self.custo_terreno {
+            Some(custo) => base * custo[(y * self.image.width() + x) as usize],
+            None => base,
+        }
+    }
+
+    /// Carrega a camada de custo de uma imagem do mesmo tamanho do mapa (ver `FonteCusto`)
+    pub fn carregar_camada_custo(&mut self, file_path: &str, fonte: &FonteCusto) -> Result<(), String> {
+        self.definir_camada_custo(&abrir_imagem(file_path)?, fonte)
+    }
+
+    /// Define a camada de custo a partir de uma imagem em memória do mesmo tamanho do mapa
+    pub fn definir_camada_custo(&mut self, img: &DynamicImage, fonte: &FonteCusto) -> Result<(), String> {
+        if img.width() != self.image.width() || img.height() != self.image.height() {
+            return Err(format!(
+                "A camada de custo tem {}x{} pixels, mas o mapa tem {}x{}.",
+                img.width(), img.height(), self.image.width(), self.image.height()
+            ));
+        }
+        self.custo_terreno = Some(ler_camada_custo(img, fonte)?);
+        Ok(())
+    }
+
+    /// Volta ao custo uniforme
+    pub fn remover_camada_custo(&mut self) {
+        self.custo_terreno = None;
+    }
+
+    /// Custo de percorrer o segmento: a integral do `custo_pixel` ao longo dele, pela regra do trapézio nos
+    /// mesmos pontos que o `is_path_colliding` confere. Em espaço livre sem camada de custo é igual ao
+    /// comprimento; infinito se colidir. `num_steps` menor que 1 conta como 1 (só as duas pontas).
+    pub fn custo_segmento(&self, start: &Point, end: &Point, num_steps: i32) -> f64 {
+        let num_steps = num_steps.max(1);
+        let dx = end.x - start.x;
+        let dy = end.y - start.y;
+
+        let mut soma = 0.0;
+        for i in 0..=num_steps {
+            let t = i as f64 / num_steps as f64;
+            let (px, py) = ((start.x + t * dx).round() as u32, (start.y + t * dy).round() as u32);
+            let (img_width, img_height) = self.image.dimensions();
+            if px >= img_width || py >= img_height {
+                return f64::INFINITY;
+            }
+            // As pontas contam meio intervalo cada
+            let peso = if i == 0 || i == num_steps { 0.5 } else { 1.0 };
+            soma += peso * self.custo_pixel(px, py);
+        }
+        start.dist(end) * (soma / num_steps as f64)
+    }
+
+    /// Rotula as regiões livres conexas da imagem inteira por flood fill (ignorando uma restrição já aplicada).
+    /// Células desconhecidas contam como livres ou não conforme a `politica_desconhecido` atual.
+    pub fn regioes_livres(&self) -> RegioesLivres {
+        let (largura, altura) = self.image.dimensions();
+        let mut rotulos: Vec<Option<usize>> = vec![None; (largura * altura) as usize];
+        let mut tamanhos = Vec::new();
+
+        for inicio in 0..(largura * altura) {
+            let (x0, y0) = (inicio % largura, inicio / largura);
+            if rotulos[inicio as usize].is_some() || self.ocupado_sem_restricao(x0, y0) {
+                continue;
+            }
+
+            let regiao = tamanhos.len();
+            let mut tamanho = 0;
+            let mut pilha = vec![(x0, y0)];
+            rotulos[inicio as usize] = Some(regiao);
+            while let Some((x, y)) = pilha.pop() {
+                tamanho += 1;
+                let vizinhos = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+                for (nx, ny) in vizinhos {
+                    if nx < largura && ny < altura {
+                        let i = (ny * largura + nx) as usize;
+                        if rotulos[i].is_none() && !self.ocupado_sem_restricao(nx, ny) {
+                            rotulos[i] = Some(regiao);
+                            pilha.push((nx, ny));
+                        }
+                    }
+                }
+            }
+            tamanhos.push(tamanho);
+        }
+
+        RegioesLivres { largura, rotulos, tamanhos }
+    }
+
+    /// Restringe o mapa à região livre que contém `semente`: os outros pixels passam a contar como obstáculo
+    /// (amostragem, checagem de colisão e consultas) e `pixel_bounds` vira o retângulo envolvente da região.
+    /// Substitui uma restrição anterior.
+    pub fn restringir_a_regiao(&mut self, semente: &Point) -> Result<(), String> {
+        let (largura, altura) = self.image.dimensions();
+        let (sx, sy) = (semente.x.round(), semente.y.round());
+        if sx < 0.0 || sy < 0.0 || sx >= largura as f64 || sy >= altura as f64 || self.ocupado_sem_restricao(sx as u32, sy as u32) {
+            return Err(format!("A semente ({}, {}) está fora da imagem ou em um obstáculo.", semente.x, semente.y));
+        }
+        let regioes = self.regioes_livres();
+        let regiao = regioes.regiao_do_pixel(sx as u32, sy as u32).expect("A semente é livre, então tem região.");
+        self.aplicar_regiao(&regioes, regiao);
+        Ok(())
+    }
+
+    /// Restringe o mapa à maior região livre (ver `restringir_a_regiao`)
+    pub fn restringir_a_maior_regiao(&mut self) -> Result<(), String> {
+        let regioes = self.regioes_livres();
+        let regiao = regioes.maior().ok_or("O mapa não tem nenhum pixel livre.")?;
+        self.aplicar_regiao(&regioes, regiao);
+        Ok(())
+    }
+
+    fn aplicar_regiao(&mut self, regioes: &RegioesLivres, regiao: usize) {
+        let mascara: Vec<bool> = regioes.rotulos.iter().map(|&r| r == Some(regiao)).collect();
+        let (largura, altura) = self.image.dimensions();
+        self.pixel_bounds = find_bounds_por(largura, altura, |x, y| !mascara[(y * largura + x) as usize]);
+        self.alcancavel = Some(mascara);
+    }
+
+    /// Desfaz a restrição de região e volta aos limites originais
+    pub fn remover_restricao(&mut self) {
+        self.alcancavel = None;
+        let (largura, altura) = self.image.dimensions();
+        let estados = &self.estados;
+        self.pixel_bounds = find_bounds_por(largura, altura, |x, y| estados[(y * largura + x) as usize] == EstadoCelula::Ocupado);
+    }
+
+    /// Pinta com `estado` os pixels do retângulo [x_min, x_max] x [y_min, y_max] (cortado na imagem) que
+    /// satisfazem `dentro`, atualizando também a cor da imagem. Retorna o retângulo alterado, ou None se ficou
+    /// todo fora da imagem ou não contém nenhum centro de pixel (ex.: um círculo de raio 0.2 entre dois pixels).
+    fn pintar(&mut self, limites: (f64, f64, f64, f64), estado: EstadoCelula, dentro: impl Fn(&Point) -> bool) -> Option<(u32, u32, u32, u32)> {
+        let (largura, altura) = self.image.dimensions();
+        let (x_min, y_min, x_max, y_max) = limites;
+        if x_max < 0.0 || y_max < 0.0 || x_min > (largura - 1) as f64 || y_min > (altura - 1) as f64 {
+            return None;
+        }
+        let x_min = x_min.max(0.0).ceil() as u32;
+        let y_min = y_min.max(0.0).ceil() as u32;
+        let x_max = (x_max.floor() as u32).min(largura - 1);
+        let y_max = (y_max.floor() as u32).min(altura - 1);
+        if x_min > x_max || y_min > y_max {
+            return None;
+        }
+
+        let cor = match estado {
+            EstadoCelula::Livre => Rgb([255, 255, 255]),
+            EstadoCelula::Ocupado => Rgb([0, 0, 0]),
+            EstadoCelula::Desconhecido => Rgb([205, 205, 205]),
+        };
+        for y in y_min..=y_max {
+            for x in x_min..=x_max {
+                if dentro(&Point { x: x as f64, y: y as f64 }) {
+                    self.estados[(y * largura + x) as usize] = estado;
+                    self.image.put_pixel(x, y, cor);
+                }
+            }
+        }
+        Some((x_min, y_min, x_max, y_max))
+    }
+
+    /// Marca como obstáculo (ou libera, se `ocupado` for false) os pixels do retângulo entre os dois cantos.
+    /// Retorna o retângulo de pixels alterado, que é a região a passar para `reparar_roadmap`.
+    /// `pixel_bounds` e uma restrição de região (`restringir_a_regiao`) não são recalculados.
+    pub fn editar_retangulo(&mut self, canto_a: &Point, canto_b: &Point, ocupado: bool) -> Option<(u32, u32, u32, u32)> {
+        let estado = if ocupado { EstadoCelula::Ocupado } else { EstadoCelula::Livre };
+        let limites = (canto_a.x.min(canto_b.x), canto_a.y.min(canto_b.y), canto_a.x.max(canto_b.x), canto_a.y.max(canto_b.y));
+        self.pintar(limites, estado, |_| true)
+    }
+
+    /// Como `editar_retangulo`, para os pixels a até `raio` do centro
+    pub fn editar_circulo(&mut self, centro: &Point, raio: f64, ocupado: bool) -> Option<(u32, u32, u32, u32)> {
+        let estado = if ocupado { EstadoCelula::Ocupado } else { EstadoCelula::Livre };
+        let limites = (centro.x - raio, centro.y - raio, centro.x + raio, centro.y + raio);
+        self.pintar(limites, estado, |p| p.dist(centro) <= raio)
+    }
+
+    /// Como `editar_retangulo`, para os pixels cujo centro está dentro do polígono
+    pub fn editar_poligono(&mut self, poligono: &Poligono, ocupado: bool) -> Option<(u32, u32, u32, u32)> {
+        if poligono.vertices.is_empty() {
+            return None;
+        }
+        let estado = if ocupado { EstadoCelula::Ocupado } else { EstadoCelula::Livre };
+        let limites = poligono.vertices.iter().fold(
+            (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            |(x0, y0, x1, y1), v| (x0.min(v.x), y0.min(v.y), x1.max(v.x), y1.max(v.y)),
+        );
+        self.pintar(limites, estado, |p| poligono.contem(p))
+    }
+
+    /// Área livre (em pixels²) dentro dos limites de navegação, ou seja, quantos pixels não são obstáculo
+    pub fn area_livre(&self) -> f64 {
+        let (x_min, y_min, x_max, y_max) = self.pixel_bounds;
+        let mut livres = 0usize;
+        for y in y_min..=y_max {
+            for x in x_min..=x_max {
+                if !self.pixel_ocupado(x, y) {
+                    livres += 1;
+                }
+            }
+        }
+        livres as f64
     }
 
     // Verifica o caminho
@@ -129,3 +535,141 @@ impl OccupancyMap {
         false
     }
 }
+
+/// Monta um mapa em memória para os testes: `ocupado(x, y)` diz quais pixels são obstáculo
+#[cfg(test)]
+pub(crate) fn mapa_de_teste(largura: u32, altura: u32, ocupado: impl Fn(u32, u32) -> bool) -> OccupancyMap {
+    let linhas: Vec<Vec<bool>> = (0..altura).map(|y| (0..largura).map(|x| ocupado(x, y)).collect()).collect();
+    OccupancyMap::de_grade(&linhas).expect("A grade de teste é retangular e não vazia.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Mapa 12x8 com moldura e uma parede em x = 8: região esquerda (x de 1 a 7) e bolsão direito (x = 9 e 10)
+    fn mapa_com_bolsao() -> OccupancyMap {
+        mapa_de_teste(12, 8, |x, y| x == 0 || y == 0 || x == 11 || y == 7 || x == 8)
+    }
+
+    /// Corredor 10x3 todo livre, com a coluna x = 5 cinza (desconhecida pela faixa do classificador)
+    fn corredor_com_desconhecido() -> OccupancyMap {
+        let image = ImageBuffer::from_fn(10, 3, |x, _| if x == 5 { Rgb([128, 128, 128]) } else { Rgb([255, 255, 255]) });
+        let classificador = ClassificadorOcupacao { desconhecido: Some((100, 150)), ..Default::default() };
+        OccupancyMap::de_buffer(image, &classificador).unwrap()
+    }
+
+    #[test]
+    fn de_grade_monta_o_mapa() {
+        let map = mapa_com_bolsao();
+        assert_eq!(map.image.dimensions(), (12, 8));
+        assert!(map.pixel_ocupado(0, 0) && map.pixel_ocupado(8, 3));
+        assert!(!map.pixel_ocupado(3, 3));
+        assert_eq!(map.estado_pixel(3, 3), EstadoCelula::Livre);
+        assert_eq!(*map.image.get_pixel(8, 3), Rgb([0, 0, 0]));
+    }
+
+    #[test]
+    fn construtores_em_memoria_recusam_entrada_invalida() {
+        assert!(OccupancyMap::de_grade(&[]).is_err());
+        assert!(OccupancyMap::de_grade(&[vec![], vec![]]).is_err());
+        assert!(OccupancyMap::de_grade(&[vec![false; 3], vec![false; 2]]).is_err());
+        assert!(OccupancyMap::de_bytes(&[1, 2, 3], &ClassificadorOcupacao::default()).is_err());
+        assert!(OccupancyMap::try_new("nao_existe.png").is_err());
+    }
+
+    #[test]
+    fn de_bytes_e_de_buffer_iguais_a_de_grade() {
+        let grade = mapa_com_bolsao();
+        let mut bytes = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(grade.image.clone()).write_to(&mut bytes, image::ImageOutputFormat::Png).unwrap();
+
+        let de_bytes = OccupancyMap::de_bytes(bytes.get_ref(), &ClassificadorOcupacao::default()).unwrap();
+        let de_buffer = OccupancyMap::de_buffer(grade.image.clone(), &ClassificadorOcupacao::default()).unwrap();
+        for map in [de_bytes, de_buffer] {
+            assert_eq!(map.pixel_bounds, grade.pixel_bounds);
+            for y in 0..8 {
+                for x in 0..12 {
+                    assert_eq!(map.pixel_ocupado(x, y), grade.pixel_ocupado(x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn desconhecido_segue_a_politica_do_mapa() {
+        let mut map = corredor_com_desconhecido();
+        let (a, b) = (Point { x: 0.0, y: 1.0 }, Point { x: 9.0, y: 1.0 });
+        assert_eq!(map.estado_pixel(5, 1), EstadoCelula::Desconhecido);
+
+        // Padrão: proibido
+        assert!(map.pixel_ocupado(5, 1));
+        assert!(map.is_path_colliding(&a, &b, 9));
+        assert!(map.custo_segmento(&a, &b, 9).is_infinite());
+
+        // Trapézio nos 10 pontos conferidos (extremos com peso 1/2), um do meio custando 3: 9 * (1 + 7 + 3) / 9
+        map.definir_politica_desconhecido(PoliticaDesconhecido::PermitirComCusto(3.0)).unwrap();
+        assert!(!map.pixel_ocupado(5, 1));
+        assert_eq!(map.custo_pixel(5, 1), 3.0);
+        assert!((map.custo_segmento(&a, &b, 9) - 11.0).abs() < 1e-12);
+
+        map.definir_politica_desconhecido(PoliticaDesconhecido::Otimista).unwrap();
+        assert_eq!(map.custo_pixel(5, 1), 1.0);
+        assert!((map.custo_segmento(&a, &b, 9) - 9.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn regioes_livres_separa_o_bolsao() {
+        let regioes = mapa_com_bolsao().regioes_livres();
+        assert_eq!(regioes.tamanhos, vec![7 * 6, 2 * 6]);
+        assert_eq!(regioes.maior(), Some(0));
+        assert_eq!(regioes.regiao_do_pixel(9, 3), Some(1));
+        assert_eq!(regioes.regiao_do_pixel(8, 3), None);
+    }
+
+    #[test]
+    fn restringir_a_regiao_bloqueia_o_resto() {
+        let mut map = mapa_com_bolsao();
+        let limites_originais = map.pixel_bounds;
+
+        map.restringir_a_regiao(&Point { x: 9.0, y: 3.0 }).unwrap();
+        assert_eq!(map.pixel_bounds, (9, 1, 10, 6));
+        assert!(map.pixel_ocupado(3, 3) && !map.pixel_alcancavel(3, 3));
+        assert!(!map.pixel_ocupado(10, 6));
+        assert!(map.is_path_colliding(&Point { x: 9.0, y: 3.0 }, &Point { x: 3.0, y: 3.0 }, 12));
+
+        map.restringir_a_maior_regiao().unwrap();
+        assert!(map.is_obstructed(&Point { x: 9.0, y: 3.0 }));
+        assert!(!map.is_obstructed(&Point { x: 3.0, y: 3.0 }));
+
+        assert!(map.restringir_a_regiao(&Point { x: 8.0, y: 3.0 }).is_err());
+        assert!(map.restringir_a_regiao(&Point { x: 50.0, y: 3.0 }).is_err());
+
+        map.remover_restricao();
+        assert_eq!(map.pixel_bounds, limites_originais);
+        assert!(!map.pixel_ocupado(9, 3) && !map.pixel_ocupado(3, 3));
+    }
+
+    #[test]
+    fn politica_desconhecido_confere_o_multiplicador() {
+        let mut map = mapa_com_bolsao();
+        for invalido in [f64::INFINITY, f64::NAN, 0.5] {
+            assert!(map.definir_politica_desconhecido(PoliticaDesconhecido::PermitirComCusto(invalido)).is_err());
+        }
+        assert_eq!(map.politica_desconhecido(), PoliticaDesconhecido::Proibir);
+        map.definir_politica_desconhecido(PoliticaDesconhecido::PermitirComCusto(3.0)).unwrap();
+        assert_eq!(map.politica_desconhecido(), PoliticaDesconhecido::PermitirComCusto(3.0));
+    }
+
+    #[test]
+    fn custo_segmento_em_espaco_livre_e_o_comprimento() {
+        let map = mapa_com_bolsao();
+        let (a, b) = (Point { x: 1.0, y: 1.0 }, Point { x: 5.0, y: 4.0 });
+        for passos in [0, 1, 3, 10] {
+            assert!((map.custo_segmento(&a, &b, passos) - 5.0).abs() < 1e-12);
+        }
+        assert_eq!(map.custo_segmento(&a, &a, 0), 0.0);
+        assert!(map.custo_segmento(&a, &Point { x: 10.0, y: 1.0 }, 10).is_infinite());
+    }
+}
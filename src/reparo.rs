@@ -0,0 +1,333 @@
+
+//! #  Reparo do roadmap depois de editar o mapa
+//! Quando uma porta fecha ou um móvel muda de lugar, o mapa é editado (`editar_retangulo`, `editar_circulo`,
+//! `editar_poligono`) e o roadmap é consertado só em volta da região alterada, sem refazer tudo:
+//! 1. remove os vértices que agora estão em obstáculo e as arestas que passam pela região e agora colidem;
+//! 2. amostra vértices novos dentro da região e os conecta como no PRM;
+//! 3. no modo por raio, tenta de novo os pares antigos cujo segmento atravessa a região (pode ter sido liberada).
+//!
+//! `atualizar_agm` então conserta a AGM antiga: só procura substitutas para as arestas da árvore que sumiram
+//! e só compara a árvore com as arestas novas, dando a mesma árvore que o `kruskal` do roadmap reparado.
+//! ## Output esperado
+//! O roadmap reparado, um `RelatorioReparo` com o que mudou e a AGM atualizada.
+
+#![allow(warnings)]
+
+use crate::dsu::DSU;
+use crate::prm_generator::{passos_colisao, ModoConexao, TENTATIVAS_POR_VERTICE};
+use crate::readmap::OccupancyMap;
+use crate::structs::{Graph, Point, UndirEdge};
+use rand::Rng;
+use std::collections::HashSet;
+
+/// O que o reparo mudou no roadmap
+#[derive(Debug, Clone, Default)]
+pub struct RelatorioReparo {
+    pub vertices_removidos: usize,
+    pub arestas_removidas: usize,
+    pub vertices_adicionados: usize,
+    /// Arestas novas, com os índices do roadmap reparado (inclui as dos vértices novos)
+    pub arestas_adicionadas: Vec<(usize, usize)>,
+    /// Índice no roadmap reparado de cada vértice do roadmap antigo (None se foi removido)
+    pub novo_indice: Vec<Option<usize>>,
+}
+
+/// Retorna true se o retângulo envolvente do segmento encosta na região (com 1 pixel de folga pelo arredondamento)
+fn segmento_toca_regiao(a: &Point, b: &Point, regiao: (u32, u32, u32, u32)) -> bool {
+    let (x0, y0, x1, y1) = regiao;
+    a.x.max(b.x) >= x0 as f64 - 1.0 && a.x.min(b.x) <= x1 as f64 + 1.0
+        && a.y.max(b.y) >= y0 as f64 - 1.0 && a.y.min(b.y) <= y1 as f64 + 1.0
+}
+
+fn segmento_livre(map: &OccupancyMap, a: &Point, b: &Point) -> bool {
+    !map.is_path_colliding(a, b, passos_colisao(a.dist(b)))
+}
+
+/// Adiciona a aresta com o `custo_segmento` como peso. Retorna false (sem aresta) se o custo for infinito.
+fn adicionar_aresta(graph: &mut Graph, map: &OccupancyMap, a: usize, b: usize, relatorio: &mut RelatorioReparo) -> bool {
+    let (p, q) = (graph.vertices[a], graph.vertices[b]);
+    let peso = map.custo_segmento(&p, &q, passos_colisao(p.dist(&q)));
+    if !peso.is_finite() {
+        return false;
+    }
+    graph.add_edge(a, b, peso).expect("Os índices são válidos e o peso é finito.");
+    relatorio.arestas_adicionadas.push((a.min(b), a.max(b)));
+    true
+}
+
+/// Conserta o roadmap depois de o mapa ser editado em `regiao` (o retângulo devolvido pelas funções de edição).
+/// `modo` é o mesmo modo de conexão usado para gerar o roadmap e `novas_amostras` é quantos vértices amostrar
+/// dentro da região. Os vértices que sobram mantêm a ordem, então os índices antigos viram `novo_indice`.
+pub fn reparar_roadmap(roadmap: &Graph, map: &OccupancyMap, regiao: (u32, u32, u32, u32), modo: ModoConexao, novas_amostras: usize, rng: &mut impl Rng) -> (Graph, RelatorioReparo) {
+    let modo = modo.resolver(map, roadmap.vertices.len() + novas_amostras);
+    let mut relatorio = RelatorioReparo::default();
+
+    // 1. Vértices que agora estão em obstáculo (só os da região podem ter mudado)
+    let mut graph = Graph::new();
+    for v in &roadmap.vertices {
+        if segmento_toca_regiao(v, v, regiao) && map.is_obstructed(v) {
+            relatorio.novo_indice.push(None);
+            relatorio.vertices_removidos += 1;
+        } else {
+            relatorio.novo_indice.push(Some(graph.add_vertex(*v)));
+        }
+    }
+
+    // Arestas antigas: somem as que perderam um vértice ou que passam pela região e agora colidem
+    for edge in roadmap.get_undirected_edges() {
+        let (a, b) = (roadmap.vertices[edge.from], roadmap.vertices[edge.to]);
+        match (relatorio.novo_indice[edge.from], relatorio.novo_indice[edge.to]) {
+            (Some(i), Some(j)) if !segmento_toca_regiao(&a, &b, regiao) || segmento_livre(map, &a, &b) => {
+                graph.add_edge_peso(i, j, edge.weight).expect("Os índices novos são válidos.");
+            }
+            _ => relatorio.arestas_removidas += 1,
+        }
+    }
+
+    // 2. Vértices novos dentro da região (nenhum se a região for vazia)
+    let (x0, y0, x1, y1) = regiao;
+    let antigos = graph.vertices.len();
+    let amostras = if x0 <= x1 && y0 <= y1 { novas_amostras } else { 0 };
+    for _ in 0..amostras {
+        let amostra = (0..TENTATIVAS_POR_VERTICE)
+            .map(|_| Point { x: rng.gen_range(x0 as f64..=x1 as f64), y: rng.gen_range(y0 as f64..=y1 as f64) })
+            .find(|p| !map.is_obstructed(p));
+        if let Some(p) = amostra {
+            graph.add_vertex(p);
+            relatorio.vertices_adicionados += 1;
+        }
+    }
+
+    for novo in antigos..graph.vertices.len() {
+        let p = graph.vertices[novo];
+        let mut candidatos: Vec<(f64, usize)> = (0..graph.vertices.len())
+            .filter(|&j| j != novo && !graph.adj[novo].iter().any(|e| e.to_idx == j))
+            .map(|j| (p.dist(&graph.vertices[j]), j))
+            .collect();
+        candidatos.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+        let mut conectados = 0;
+        for (dist, j) in candidatos {
+            let continuar = match modo {
+                ModoConexao::Raio(r) => dist <= r,
+                ModoConexao::KVizinhos { k, .. } => conectados < k,
+                _ => unreachable!("O modo já foi resolvido."),
+            };
+            if !continuar {
+                break;
+            }
+            if segmento_livre(map, &p, &graph.vertices[j]) && adicionar_aresta(&mut graph, map, novo, j, &mut relatorio) {
+                conectados += 1;
+            }
+        }
+    }
+
+    // 3. Pares antigos que atravessam a região e podem ter ficado livres (só no modo por raio)
+    if let ModoConexao::Raio(r) = modo {
+        let perto: Vec<usize> = (0..antigos)
+            .filter(|&i| {
+                let v = graph.vertices[i];
+                v.x >= x0 as f64 - r && v.x <= x1 as f64 + r && v.y >= y0 as f64 - r && v.y <= y1 as f64 + r
+            })
+            .collect();
+        for (n, &i) in perto.iter().enumerate() {
+            for &j in &perto[n + 1..] {
+                let (a, b) = (graph.vertices[i], graph.vertices[j]);
+                if a.dist(&b) <= r
+                    && segmento_toca_regiao(&a, &b, regiao)
+                    && !graph.adj[i].iter().any(|e| e.to_idx == j)
+                    && segmento_livre(map, &a, &b)
+                {
+                    adicionar_aresta(&mut graph, map, i, j, &mut relatorio);
+                }
+            }
+        }
+    }
+
+    (graph, relatorio)
+}
+
+/// Atualiza a AGM do roadmap antigo para o roadmap reparado, sem rodar o Kruskal no grafo inteiro:
+/// 1. as arestas da árvore que continuam no roadmap formam uma floresta (continuam na AGM, pois remover arestas
+///    não muda o corte mínimo de uma aresta que ficou); se alguma sumiu, só as arestas antigas que ligam
+///    componentes diferentes da floresta são ordenadas e usadas para religá-las;
+/// 2. a árvore resultante é combinada só com as arestas novas (uma aresta nova entra se for mais leve que
+///    a aresta mais pesada do ciclo que ela fecha).
+/// O resultado é igual ao `kruskal` do roadmap reparado (mesma ordem de desempate). Mesmo contrato do `kruskal`:
+/// erro se o roadmap reparado não for conexo.
+pub fn atualizar_agm(agm: &Graph, roadmap: &Graph, relatorio: &RelatorioReparo) -> Result<Graph, String> {
+    let n = roadmap.vertices.len();
+    let novas: HashSet<(usize, usize)> = relatorio.arestas_adicionadas.iter().copied().collect();
+
+    // Arestas da AGM antiga que continuam no roadmap (a floresta)
+    let mut floresta: Vec<UndirEdge> = Vec::new();
+    let mut removidas_da_arvore = 0;
+    for edge in agm.get_undirected_edges() {
+        let par = (relatorio.novo_indice[edge.from], relatorio.novo_indice[edge.to]);
+        match par {
+            (Some(i), Some(j)) if roadmap.adj[i].iter().any(|e| e.to_idx == j && e.weight == edge.weight) => {
+                floresta.push(UndirEdge { from: i.min(j), to: i.max(j), weight: edge.weight });
+            }
+            _ => removidas_da_arvore += 1,
+        }
+    }
+
+    // 1. Religa a floresta com as arestas que já existiam (as novas entram no passo 2)
+    let mut arvore = floresta.clone();
+    if removidas_da_arvore > 0 {
+        let mut dsu = DSU::new(n);
+        for edge in &floresta {
+            dsu.union(edge.from, edge.to);
+        }
+        // Só as arestas entre componentes diferentes podem religar a floresta
+        let mut entre_componentes: Vec<UndirEdge> = roadmap
+            .get_undirected_edges()
+            .into_iter()
+            .filter(|e| !novas.contains(&(e.from, e.to)) && !dsu.conectados(e.from, e.to))
+            .collect();
+        entre_componentes.sort();
+        for edge in entre_componentes {
+            if dsu.union(edge.from, edge.to) {
+                arvore.push(edge);
+            }
+        }
+    }
+
+    // 2. Kruskal só na árvore + arestas novas
+    let mut candidatas = arvore;
+    candidatas.extend(
+        roadmap
+            .get_undirected_edges()
+            .into_iter()
+            .filter(|e| novas.contains(&(e.from, e.to))),
+    );
+    candidatas.sort();
+
+    let mut dsu = DSU::new(n);
+    let mut nova_agm = Graph::new();
+    for &v in &roadmap.vertices {
+        nova_agm.add_vertex(v);
+    }
+    for edge in candidatas {
+        if dsu.union(edge.from, edge.to) {
+            nova_agm.add_edge_peso(edge.from, edge.to, edge.weight)?;
+        }
+    }
+
+    if dsu.num_componentes() > 1 {
+        return Err(format!("O grafo não é conexo. Existem {} componentes.", dsu.num_componentes()));
+    }
+    Ok(nova_agm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amostragem::AmostradorHalton;
+    use crate::kruskal::kruskal;
+    use crate::prm_generator::generate_prm_com_amostrador;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Mapa 60x60 livre com moldura de obstáculo
+    fn mapa_vazio() -> OccupancyMap {
+        let grade: Vec<Vec<bool>> = (0..60)
+            .map(|y| (0..60).map(|x| x == 0 || y == 0 || x == 59 || y == 59).collect())
+            .collect();
+        OccupancyMap::de_grade(&grade).unwrap()
+    }
+
+    fn arestas_ordenadas(g: &Graph) -> Vec<UndirEdge> {
+        let mut arestas = g.get_undirected_edges();
+        arestas.sort();
+        arestas
+    }
+
+    /// Edita o mapa, repara o roadmap e confere `atualizar_agm` contra o `kruskal` do roadmap reparado
+    fn conferir_edicao(map: &mut OccupancyMap, roadmap: &Graph, agm: &Graph, modo: ModoConexao, a: Point, b: Point, ocupado: bool) -> (Graph, Result<Graph, String>) {
+        let regiao = map.editar_retangulo(&a, &b, ocupado).unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+        let (reparado, relatorio) = reparar_roadmap(roadmap, map, regiao, modo, 5, &mut rng);
+        let atualizada = atualizar_agm(agm, &reparado, &relatorio);
+        match (&atualizada, kruskal(&reparado)) {
+            (Ok(a), Ok(k)) => assert_eq!(arestas_ordenadas(a), arestas_ordenadas(&k)),
+            (Err(_), Err(_)) => {}
+            (a, k) => panic!("atualizar_agm = {:?}, kruskal = {:?}", a.is_ok(), k.is_ok()),
+        }
+        (reparado, atualizada)
+    }
+
+    #[test]
+    fn atualizar_agm_igual_ao_kruskal() {
+        for modo in [ModoConexao::Raio(12.0), ModoConexao::KVizinhos { k: 6, mutuo: false }] {
+            let mut map = mapa_vazio();
+            let roadmap = generate_prm_com_amostrador(&map, 150, modo, &mut AmostradorHalton::default());
+            let agm = kruskal(&roadmap).unwrap();
+
+            // Fecha uma parede parcial no meio e depois a abre de novo
+            let (a, b) = (Point { x: 28.0, y: 10.0 }, Point { x: 32.0, y: 45.0 });
+            let (fechado, agm_fechado) = conferir_edicao(&mut map, &roadmap, &agm, modo, a, b, true);
+            let agm_fechado = agm_fechado.expect("A parede parcial não desconecta o mapa.");
+            assert!(fechado.vertices.iter().all(|v| !map.is_obstructed(v)));
+            conferir_edicao(&mut map, &fechado, &agm_fechado, modo, a, b, false).1.unwrap();
+        }
+    }
+
+    #[test]
+    fn atualizar_agm_erro_se_desconexo() {
+        let modo = ModoConexao::Raio(12.0);
+        let mut map = mapa_vazio();
+        let roadmap = generate_prm_com_amostrador(&map, 150, modo, &mut AmostradorHalton::default());
+        let agm = kruskal(&roadmap).unwrap();
+
+        // Uma parede de cima a baixo separa o mapa em dois
+        let (_, resultado) = conferir_edicao(&mut map, &roadmap, &agm, modo, Point { x: 28.0, y: 0.0 }, Point { x: 32.0, y: 59.0 }, true);
+        assert!(resultado.is_err());
+    }
+
+    #[test]
+    fn reparo_remove_o_que_colide_e_amostra_na_regiao() {
+        let modo = ModoConexao::Raio(12.0);
+        let mut map = mapa_vazio();
+        let roadmap = generate_prm_com_amostrador(&map, 150, modo, &mut AmostradorHalton::default());
+        let regiao = map.editar_retangulo(&Point { x: 20.0, y: 20.0 }, &Point { x: 35.0, y: 30.0 }, true).unwrap();
+        assert_eq!(regiao, (20, 20, 35, 30));
+
+        // Libera uma faixa dentro do bloco para os vértices novos terem onde cair
+        let livre = map.editar_retangulo(&Point { x: 26.0, y: 20.0 }, &Point { x: 29.0, y: 30.0 }, false).unwrap();
+        let mut rng = StdRng::seed_from_u64(3);
+        let (reparado, relatorio) = reparar_roadmap(&roadmap, &map, regiao, modo, 4, &mut rng);
+
+        assert!(relatorio.vertices_removidos > 0 && relatorio.arestas_removidas > 0);
+        assert_eq!(relatorio.vertices_adicionados, 4);
+        assert_eq!(reparado.vertices.len(), roadmap.vertices.len() - relatorio.vertices_removidos + 4);
+        for (antigo, novo) in relatorio.novo_indice.iter().enumerate() {
+            if let Some(novo) = novo {
+                assert_eq!(reparado.vertices[*novo].dist(&roadmap.vertices[antigo]), 0.0);
+            }
+        }
+        for v in &reparado.vertices[reparado.vertices.len() - 4..] {
+            assert!(!map.is_obstructed(v));
+            assert!(v.x >= livre.0 as f64 - 0.5 && v.x <= livre.2 as f64 + 0.5);
+        }
+        for edge in reparado.get_undirected_edges() {
+            let (a, b) = (reparado.vertices[edge.from], reparado.vertices[edge.to]);
+            assert!(segmento_livre(&map, &a, &b), "a aresta ({}, {}) colide", edge.from, edge.to);
+        }
+        for &(a, b) in &relatorio.arestas_adicionadas {
+            assert!(reparado.adj[a].iter().any(|e| e.to_idx == b));
+        }
+    }
+
+    #[test]
+    fn regiao_vazia_nao_amostra() {
+        let mut map = mapa_vazio();
+        assert_eq!(map.editar_circulo(&Point { x: 10.3, y: 10.3 }, 0.2, true), None);
+
+        let roadmap = generate_prm_com_amostrador(&map, 50, ModoConexao::Raio(15.0), &mut AmostradorHalton::default());
+        let mut rng = StdRng::seed_from_u64(1);
+        let (reparado, relatorio) = reparar_roadmap(&roadmap, &map, (11, 11, 10, 10), ModoConexao::Raio(15.0), 3, &mut rng);
+        assert_eq!(relatorio.vertices_adicionados, 0);
+        assert_eq!(reparado.vertices.len(), roadmap.vertices.len());
+    }
+}
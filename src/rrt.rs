@@ -0,0 +1,359 @@
+
+//! #  Planejadores de consulta única: RRT e RRT-Connect
+//! Para uma consulta isolada em um mapa novo, montar o roadmap inteiro e a AGM é desperdício.
+//! Esses planejadores crescem árvores a partir do início (e do destino, no RRT-Connect) até ligar os dois pontos.
+//! ## Output esperado
+//! A árvore explorada como `Graph` (pode ser salva com `save_graph_to_csv`) e o caminho como lista de índices
+//! dessa árvore, no mesmo formato do `dfs_path` (pode ser salvo com `path_export`).
+
+#![allow(warnings)]
+
+use crate::readmap::OccupancyMap;
+use crate::structs::{Graph, Point};
+use crate::prm_generator::{sample_valid_point, passos_colisao};
+use rand::Rng;
+
+/// Parâmetros dos planejadores RRT
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigRrt {
+    /// Distância máxima (em pixels) que a árvore cresce por iteração
+    pub passo: f64,
+    /// Quantidade máxima de amostras antes de desistir
+    pub max_iteracoes: usize,
+    /// Probabilidade de usar o destino como amostra (puxa a árvore para o objetivo)
+    pub prob_objetivo: f64,
+}
+
+impl Default for ConfigRrt {
+    fn default() -> Self {
+        ConfigRrt { passo: 20.0, max_iteracoes: 10000, prob_objetivo: 0.05 }
+    }
+}
+
+impl ConfigRrt {
+    /// Confere os parâmetros: `passo` finito e positivo (com 0 a árvore nunca sai do lugar) e
+    /// `prob_objetivo` em [0, 1]
+    pub fn validar(&self) -> Result<(), String> {
+        conferir_parametros(self.passo, self.prob_objetivo)
+    }
+}
+
+/// Validação comum às configurações do RRT e do RRT*
+pub(crate) fn conferir_parametros(passo: f64, prob_objetivo: f64) -> Result<(), String> {
+    if !passo.is_finite() || passo <= 0.0 {
+        return Err(format!("O passo precisa ser finito e positivo, veio {}.", passo));
+    }
+    if !(0.0..=1.0).contains(&prob_objetivo) {
+        return Err(format!("A probabilidade do objetivo precisa estar em [0, 1], veio {}.", prob_objetivo));
+    }
+    Ok(())
+}
+
+/// Resultado de um planejador RRT
+#[derive(Debug)]
+pub struct ResultadoRrt {
+    /// Todos os vértices e arestas explorados
+    pub arvore: Graph,
+    /// Índices (em `arvore`) do caminho do início ao destino, se encontrado
+    pub caminho: Option<Vec<usize>>,
+    /// Quantas iterações foram usadas
+    pub iteracoes: usize,
+}
+
+/// Árvore que cresce a partir de uma raíz, guardando o pai de cada vértice
+struct Arvore {
+    graph: Graph,
+    pai: Vec<Option<usize>>,
+}
+
+/// Resultado de uma tentativa de crescer a árvore em direção a um ponto
+enum Extensao {
+    /// O segmento até o novo ponto colide
+    Preso,
+    /// Um novo vértice foi adicionado, mas o alvo ainda não foi alcançado
+    Avancou(usize),
+    /// O alvo está a menos de um passo e com caminho livre a partir deste vértice (nada é adicionado)
+    Alcancou(usize),
+}
+
+impl Arvore {
+    fn new(raiz: Point) -> Self {
+        let mut graph = Graph::new();
+        graph.add_vertex(raiz);
+        Arvore { graph, pai: vec![None] }
+    }
+
+    /// Índice do vértice mais próximo de `p`
+    fn mais_proximo(&self, p: &Point) -> usize {
+        let mut melhor = 0;
+        let mut menor_dist = f64::MAX;
+        for (i, v) in self.graph.vertices.iter().enumerate() {
+            let dist = v.dist(p);
+            if dist < menor_dist {
+                menor_dist = dist;
+                melhor = i;
+            }
+        }
+        melhor
+    }
+
+    /// Adiciona `p` como filho de `pai`
+    fn adicionar(&mut self, p: Point, pai: usize) -> usize {
+        let idx = self.graph.add_vertex(p);
+        self.graph.add_edge_dist(pai, idx);
+        self.pai.push(Some(pai));
+        idx
+    }
+
+    /// Dá um passo do vértice mais próximo em direção a `alvo`
+    fn estender(&mut self, map: &OccupancyMap, alvo: &Point, passo: f64) -> Extensao {
+        let perto = self.mais_proximo(alvo);
+        let origem = self.graph.vertices[perto];
+        let dist = origem.dist(alvo);
+
+        // Alvo a menos de um passo: só confere se dá para ligar direto
+        if dist <= passo {
+            if map.is_path_colliding(&origem, alvo, passos_colisao(dist)) {
+                return Extensao::Preso;
+            }
+            return Extensao::Alcancou(perto);
+        }
+
+        let t = passo / dist;
+        let novo = Point { x: origem.x + t * (alvo.x - origem.x), y: origem.y + t * (alvo.y - origem.y) };
+        if map.is_path_colliding(&origem, &novo, passos_colisao(passo)) {
+            return Extensao::Preso;
+        }
+        Extensao::Avancou(self.adicionar(novo, perto))
+    }
+
+    /// Caminho da raíz até `idx`
+    fn caminho_da_raiz(&self, idx: usize) -> Vec<usize> {
+        let mut path = vec![idx];
+        let mut atual = idx;
+        while let Some(p) = self.pai[atual] {
+            path.push(p);
+            atual = p;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Confere se o início e o destino são pontos livres do mapa
+fn checar_extremos(map: &OccupancyMap, inicio: &Point, objetivo: &Point) -> Result<(), String> {
+    if map.is_obstructed(inicio) {
+        return Err("O ponto de partida está em um obstáculo.".to_string());
+    }
+    if map.is_obstructed(objetivo) {
+        return Err("O ponto de destino está em um obstáculo.".to_string());
+    }
+    Ok(())
+}
+
+/// Amostra um ponto livre do mapa ou, com probabilidade `prob_objetivo`, o próprio destino
+fn amostrar(map: &OccupancyMap, objetivo: &Point, cfg: &ConfigRrt, rng: &mut impl Rng) -> Point {
+    if rng.gen_bool(cfg.prob_objetivo) {
+        *objetivo
+    } else {
+        sample_valid_point(map, rng)
+    }
+}
+
+/// RRT clássico: uma árvore cresce a partir do início até alcançar o destino
+pub fn rrt(map: &OccupancyMap, inicio: Point, objetivo: Point, cfg: &ConfigRrt) -> Result<ResultadoRrt, String> {
+    rrt_com_rng(map, inicio, objetivo, cfg, &mut rand::thread_rng())
+}
+
+/// Igual ao `rrt`, mas amostrando com o `rng` dado (com um `StdRng` semeado o resultado é reproduzível)
+pub fn rrt_com_rng(map: &OccupancyMap, inicio: Point, objetivo: Point, cfg: &ConfigRrt, rng: &mut impl Rng) -> Result<ResultadoRrt, String> {
+    cfg.validar()?;
+    checar_extremos(map, &inicio, &objetivo)?;
+    let mut arvore = Arvore::new(inicio);
+
+    for iteracao in 1..=cfg.max_iteracoes {
+        let amostra = amostrar(map, &objetivo, cfg, rng);
+
+        let novo = match arvore.estender(map, &amostra, cfg.passo) {
+            Extensao::Preso => continue,
+            Extensao::Avancou(idx) => idx,
+            // A amostra já é alcançável direto: adiciona ela mesma
+            Extensao::Alcancou(perto) => arvore.adicionar(amostra, perto),
+        };
+
+        // Se o novo vértice enxerga o destino a menos de um passo, liga os dois e termina
+        let p = arvore.graph.vertices[novo];
+        let dist = p.dist(&objetivo);
+        if dist <= cfg.passo && !map.is_path_colliding(&p, &objetivo, passos_colisao(dist)) {
+            let fim = if dist == 0.0 { novo } else { arvore.adicionar(objetivo, novo) };
+            let caminho = arvore.caminho_da_raiz(fim);
+            return Ok(ResultadoRrt { arvore: arvore.graph, caminho: Some(caminho), iteracoes: iteracao });
+        }
+    }
+
+    Ok(ResultadoRrt { arvore: arvore.graph, caminho: None, iteracoes: cfg.max_iteracoes })
+}
+
+/// RRT-Connect: uma árvore cresce do início e outra do destino. A cada iteração uma delas dá um passo
+/// em direção a uma amostra e a outra tenta se conectar ao novo vértice, e os papéis se alternam.
+pub fn rrt_connect(map: &OccupancyMap, inicio: Point, objetivo: Point, cfg: &ConfigRrt) -> Result<ResultadoRrt, String> {
+    rrt_connect_com_rng(map, inicio, objetivo, cfg, &mut rand::thread_rng())
+}
+
+/// Igual ao `rrt_connect`, mas amostrando com o `rng` dado
+pub fn rrt_connect_com_rng(map: &OccupancyMap, inicio: Point, objetivo: Point, cfg: &ConfigRrt, rng: &mut impl Rng) -> Result<ResultadoRrt, String> {
+    cfg.validar()?;
+    checar_extremos(map, &inicio, &objetivo)?;
+
+    // `a` é a árvore que estende nesta iteração; `a_eh_inicio` diz se ela é a que começou no início
+    let mut a = Arvore::new(inicio);
+    let mut b = Arvore::new(objetivo);
+    let mut a_eh_inicio = true;
+
+    for iteracao in 1..=cfg.max_iteracoes {
+        let amostra = sample_valid_point(map, rng);
+
+        let novo_a = match a.estender(map, &amostra, cfg.passo) {
+            Extensao::Preso => None,
+            Extensao::Avancou(idx) => Some(idx),
+            Extensao::Alcancou(perto) => Some(a.adicionar(amostra, perto)),
+        };
+
+        if let Some(novo_a) = novo_a {
+            // A outra árvore avança em direção ao novo vértice até alcançá-lo ou bater em um obstáculo
+            let alvo = a.graph.vertices[novo_a];
+            loop {
+                match b.estender(map, &alvo, cfg.passo) {
+                    Extensao::Preso => break,
+                    Extensao::Avancou(_) => continue,
+                    Extensao::Alcancou(ponte_b) => {
+                        let ponte = if a_eh_inicio { (novo_a, ponte_b) } else { (ponte_b, novo_a) };
+                        let (arv_inicio, arv_fim) = if a_eh_inicio { (&a, &b) } else { (&b, &a) };
+                        let (arvore, caminho) = juntar_arvores(arv_inicio, arv_fim, Some(ponte));
+                        return Ok(ResultadoRrt { arvore, caminho, iteracoes: iteracao });
+                    }
+                }
+            }
+        }
+
+        std::mem::swap(&mut a, &mut b);
+        a_eh_inicio = !a_eh_inicio;
+    }
+
+    let (arv_inicio, arv_fim) = if a_eh_inicio { (&a, &b) } else { (&b, &a) };
+    let (arvore, caminho) = juntar_arvores(arv_inicio, arv_fim, None);
+    Ok(ResultadoRrt { arvore, caminho, iteracoes: cfg.max_iteracoes })
+}
+
+/// Junta as duas árvores do RRT-Connect em um único `Graph` (primeiro a do início, depois a do destino).
+/// Se houver `ponte` (vértice da árvore do início, vértice da árvore do destino), liga os dois
+/// e retorna também o caminho do início ao destino no grafo unido.
+fn juntar_arvores(arv_inicio: &Arvore, arv_fim: &Arvore, ponte: Option<(usize, usize)>) -> (Graph, Option<Vec<usize>>) {
+    let mut graph = Graph::new();
+    let deslocamento = arv_inicio.graph.vertices.len();
+
+    for arv in [arv_inicio, arv_fim] {
+        for &v in &arv.graph.vertices {
+            graph.add_vertex(v);
+        }
+    }
+    for (desloc, arv) in [(0, arv_inicio), (deslocamento, arv_fim)] {
+        for edge in arv.graph.get_undirected_edges() {
+            graph.add_edge_peso(edge.from + desloc, edge.to + desloc, edge.weight)
+                .expect("Os índices das duas árvores cabem no grafo unido.");
+        }
+    }
+
+    let (idx_inicio, idx_fim) = match ponte {
+        Some(p) => p,
+        None => return (graph, None),
+    };
+    graph.add_edge_dist(idx_inicio, idx_fim + deslocamento);
+
+    // Caminho: raíz do início até a ponte, depois da ponte até a raíz do destino
+    let mut caminho = arv_inicio.caminho_da_raiz(idx_inicio);
+    let mut volta = arv_fim.caminho_da_raiz(idx_fim);
+    volta.reverse();
+    caminho.extend(volta.into_iter().map(|i| i + deslocamento));
+
+    (graph, Some(caminho))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readmap::mapa_de_teste;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Mapa 80x60 com uma parede grossa (x de 36 a 43) que só deixa passagem embaixo (y de 41 a 58)
+    fn mapa_com_parede() -> OccupancyMap {
+        mapa_de_teste(80, 60, |x, y| x == 0 || y == 0 || x == 79 || y == 59 || ((36..=43).contains(&x) && y <= 40))
+    }
+
+    const INICIO: Point = Point { x: 10.0, y: 10.0 };
+    const OBJETIVO: Point = Point { x: 70.0, y: 10.0 };
+
+    /// Confere que todas as arestas da árvore são livres e que o caminho vai do início ao objetivo pelas arestas dela
+    fn conferir_resultado(map: &OccupancyMap, resultado: &ResultadoRrt, inicio: Point, objetivo: Point) {
+        let arvore = &resultado.arvore;
+        for e in arvore.get_undirected_edges() {
+            let (p, q) = (arvore.vertices[e.from], arvore.vertices[e.to]);
+            assert!(!map.is_path_colliding(&p, &q, passos_colisao(p.dist(&q))), "aresta {:?} -> {:?} colide", p, q);
+            assert!((e.weight.valor() - p.dist(&q)).abs() < 1e-9);
+        }
+
+        let caminho = resultado.caminho.as_ref().expect("o planejador deveria achar um caminho");
+        assert_eq!(arvore.vertices[caminho[0]], inicio);
+        assert_eq!(arvore.vertices[*caminho.last().unwrap()], objetivo);
+        for par in caminho.windows(2) {
+            assert!(arvore.adj[par[0]].iter().any(|e| e.to_idx == par[1]), "o caminho usa uma aresta que não está na árvore");
+        }
+    }
+
+    #[test]
+    fn rrt_contorna_a_parede() {
+        let map = mapa_com_parede();
+        let cfg = ConfigRrt { passo: 5.0, ..Default::default() };
+        for semente in 0..5 {
+            let resultado = rrt_com_rng(&map, INICIO, OBJETIVO, &cfg, &mut StdRng::seed_from_u64(semente)).unwrap();
+            conferir_resultado(&map, &resultado, INICIO, OBJETIVO);
+            assert!(resultado.iteracoes <= cfg.max_iteracoes);
+        }
+    }
+
+    #[test]
+    fn rrt_connect_contorna_a_parede() {
+        let map = mapa_com_parede();
+        let cfg = ConfigRrt { passo: 5.0, ..Default::default() };
+        for semente in 0..5 {
+            let resultado = rrt_connect_com_rng(&map, INICIO, OBJETIVO, &cfg, &mut StdRng::seed_from_u64(semente)).unwrap();
+            conferir_resultado(&map, &resultado, INICIO, OBJETIVO);
+        }
+    }
+
+    #[test]
+    fn mesma_semente_mesma_arvore() {
+        let map = mapa_com_parede();
+        let cfg = ConfigRrt { passo: 5.0, ..Default::default() };
+        let rodar = |connect: bool| {
+            let mut rng = StdRng::seed_from_u64(11);
+            if connect { rrt_connect_com_rng(&map, INICIO, OBJETIVO, &cfg, &mut rng) } else { rrt_com_rng(&map, INICIO, OBJETIVO, &cfg, &mut rng) }.unwrap()
+        };
+        for connect in [false, true] {
+            let (a, b) = (rodar(connect), rodar(connect));
+            assert_eq!((a.iteracoes, &a.caminho), (b.iteracoes, &b.caminho));
+            assert_eq!(a.arvore.vertices, b.arvore.vertices);
+        }
+    }
+
+    #[test]
+    fn extremos_em_obstaculo_sao_erro() {
+        let map = mapa_com_parede();
+        let na_parede = Point { x: 40.0, y: 10.0 };
+        let cfg = ConfigRrt::default();
+        assert!(rrt(&map, na_parede, OBJETIVO, &cfg).is_err());
+        assert!(rrt(&map, INICIO, na_parede, &cfg).is_err());
+        assert!(rrt_connect(&map, na_parede, OBJETIVO, &cfg).is_err());
+    }
+}
@@ -0,0 +1,384 @@
+
+//! #  Planejadores que melhoram o caminho com mais iterações: RRT* e Informed RRT*
+//! O RRT* escolhe o pai de menor custo entre os vizinhos dentro de um raio que diminui com o número de vértices
+//! e religa (rewiring) os vizinhos quando passar pelo novo vértice fica mais barato.
+//! O Informed RRT*, depois de achar a primeira solução, só amostra dentro da elipse com focos no início e no destino
+//! que contém todos os pontos capazes de melhorar o custo atual.
+//! ## Output esperado
+//! Além da árvore e do caminho (mesmo formato do `rrt`), o histórico do melhor custo por iteração,
+//! que pode ser salvo com `salvar_convergencia_csv` para comparar com o caminho do PRM + AGM (`comprimento_caminho`).
+
+#![allow(warnings)]
+
+use crate::readmap::OccupancyMap;
+use crate::structs::{Graph, Point};
+use crate::prm_generator::{sample_valid_point, passos_colisao, gamma_prm_estrela, raio_estrela};
+use crate::rrt::conferir_parametros;
+use csv::Writer;
+use rand::Rng;
+use std::error::Error;
+
+/// Parâmetros do RRT* e do Informed RRT*
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigRrtEstrela {
+    /// Distância máxima (em pixels) que a árvore cresce por iteração; também limita o raio de vizinhança
+    pub passo: f64,
+    /// Quantidade de iterações (o planejador usa todas, pois o caminho continua melhorando)
+    pub max_iteracoes: usize,
+    /// Probabilidade de usar o destino como amostra enquanto não há solução
+    pub prob_objetivo: f64,
+}
+
+impl Default for ConfigRrtEstrela {
+    fn default() -> Self {
+        ConfigRrtEstrela { passo: 20.0, max_iteracoes: 5000, prob_objetivo: 0.05 }
+    }
+}
+
+impl ConfigRrtEstrela {
+    /// Confere os parâmetros, como o `ConfigRrt::validar`
+    pub fn validar(&self) -> Result<(), String> {
+        conferir_parametros(self.passo, self.prob_objetivo)
+    }
+}
+
+/// Resultado do RRT* / Informed RRT*
+#[derive(Debug)]
+pub struct ResultadoRrtEstrela {
+    /// Árvore final (depois de todas as religações)
+    pub arvore: Graph,
+    /// Índices (em `arvore`) do melhor caminho do início ao destino, se encontrado
+    pub caminho: Option<Vec<usize>>,
+    /// Custo (comprimento em pixels) do melhor caminho
+    pub custo: Option<f64>,
+    /// Pares (iteração, melhor custo) registrados sempre que o melhor custo muda, mais a última iteração
+    pub convergencia: Vec<(usize, f64)>,
+}
+
+/// Estado interno da árvore do RRT*
+struct Arvore {
+    pontos: Vec<Point>,
+    pai: Vec<Option<usize>>,
+    filhos: Vec<Vec<usize>>,
+    /// Custo do caminho da raíz até cada vértice
+    custo: Vec<f64>,
+}
+
+impl Arvore {
+    fn adicionar(&mut self, p: Point, pai: usize) -> usize {
+        let idx = self.pontos.len();
+        self.custo.push(self.custo[pai] + self.pontos[pai].dist(&p));
+        self.pontos.push(p);
+        self.pai.push(Some(pai));
+        self.filhos.push(Vec::new());
+        self.filhos[pai].push(idx);
+        idx
+    }
+
+    /// Troca o pai de `v` e atualiza o custo de toda a sub-árvore de `v`
+    fn religar(&mut self, v: usize, novo_pai: usize) {
+        if let Some(antigo) = self.pai[v] {
+            self.filhos[antigo].retain(|&f| f != v);
+        }
+        self.pai[v] = Some(novo_pai);
+        self.filhos[novo_pai].push(v);
+
+        let mut pilha = vec![v];
+        while let Some(u) = pilha.pop() {
+            let p = self.pai[u].expect("Só a raíz não tem pai e ela nunca é religada.");
+            self.custo[u] = self.custo[p] + self.pontos[p].dist(&self.pontos[u]);
+            pilha.extend(self.filhos[u].iter().copied());
+        }
+    }
+}
+
+/// RRT*: amostragem uniforme durante todas as iterações
+pub fn rrt_estrela(map: &OccupancyMap, inicio: Point, objetivo: Point, cfg: &ConfigRrtEstrela) -> Result<ResultadoRrtEstrela, String> {
+    rrt_estrela_com_rng(map, inicio, objetivo, cfg, &mut rand::thread_rng())
+}
+
+/// Igual ao `rrt_estrela`, mas amostrando com o `rng` dado (com um `StdRng` semeado o resultado é reproduzível)
+pub fn rrt_estrela_com_rng(map: &OccupancyMap, inicio: Point, objetivo: Point, cfg: &ConfigRrtEstrela, rng: &mut impl Rng) -> Result<ResultadoRrtEstrela, String> {
+    planejar(map, inicio, objetivo, cfg, false, rng)
+}
+
+/// Informed RRT*: igual ao RRT*, mas depois da primeira solução só amostra na elipse que pode melhorar o custo
+pub fn informed_rrt_estrela(map: &OccupancyMap, inicio: Point, objetivo: Point, cfg: &ConfigRrtEstrela) -> Result<ResultadoRrtEstrela, String> {
+    informed_rrt_estrela_com_rng(map, inicio, objetivo, cfg, &mut rand::thread_rng())
+}
+
+/// Igual ao `informed_rrt_estrela`, mas amostrando com o `rng` dado
+pub fn informed_rrt_estrela_com_rng(map: &OccupancyMap, inicio: Point, objetivo: Point, cfg: &ConfigRrtEstrela, rng: &mut impl Rng) -> Result<ResultadoRrtEstrela, String> {
+    planejar(map, inicio, objetivo, cfg, true, rng)
+}
+
+/// Tentativas de amostrar um ponto livre na elipse antes de desistir da iteração
+const TENTATIVAS_ELIPSE: usize = 1000;
+
+/// Amostra um ponto livre dentro da elipse com focos `inicio` e `objetivo` e soma das distâncias `c_best`.
+/// Retorna None se nenhuma das `TENTATIVAS_ELIPSE` tentativas cair em espaço livre dentro dos limites do mapa.
+/// Não cai para a amostragem no mapa todo: um ponto fora da elipse não tem como melhorar a solução.
+fn amostrar_na_elipse(map: &OccupancyMap, inicio: &Point, objetivo: &Point, c_best: f64, rng: &mut impl Rng) -> Option<Point> {
+    let c_min = inicio.dist(objetivo);
+    let centro = Point { x: (inicio.x + objetivo.x) / 2.0, y: (inicio.y + objetivo.y) / 2.0 };
+    let angulo = (objetivo.y - inicio.y).atan2(objetivo.x - inicio.x);
+    let (sen, cos) = angulo.sin_cos();
+    // Semi-eixo maior na direção início -> destino e semi-eixo menor perpendicular
+    let a = c_best / 2.0;
+    let b = (c_best * c_best - c_min * c_min).max(0.0).sqrt() / 2.0;
+    let (x_min, y_min, x_max, y_max) = map.pixel_bounds;
+
+    for _ in 0..TENTATIVAS_ELIPSE {
+        // Ponto uniforme no disco unitário, esticado para a elipse e girado
+        let r = rng.gen_range(0.0..1.0f64).sqrt();
+        let theta = rng.gen_range(0.0..std::f64::consts::TAU);
+        let (ex, ey) = (a * r * theta.cos(), b * r * theta.sin());
+        let p = Point { x: centro.x + ex * cos - ey * sen, y: centro.y + ex * sen + ey * cos };
+
+        let dentro = p.x >= x_min as f64 && p.x <= x_max as f64 && p.y >= y_min as f64 && p.y <= y_max as f64;
+        if dentro && !map.is_obstructed(&p) {
+            return Some(p);
+        }
+    }
+    None
+}
+
+fn planejar(map: &OccupancyMap, inicio: Point, objetivo: Point, cfg: &ConfigRrtEstrela, informado: bool, rng: &mut impl Rng) -> Result<ResultadoRrtEstrela, String> {
+    cfg.validar()?;
+    if map.is_obstructed(&inicio) {
+        return Err("O ponto de partida está em um obstáculo.".to_string());
+    }
+    if map.is_obstructed(&objetivo) {
+        return Err("O ponto de destino está em um obstáculo.".to_string());
+    }
+
+    let gamma = gamma_prm_estrela(map);
+    let mut arvore = Arvore { pontos: vec![inicio], pai: vec![None], filhos: vec![Vec::new()], custo: vec![0.0] };
+    // Vértices que enxergam o destino a menos de um passo
+    let mut perto_objetivo: Vec<usize> = Vec::new();
+    let mut melhor: Option<(usize, f64)> = None;
+    let mut convergencia = Vec::new();
+
+    for iteracao in 1..=cfg.max_iteracoes {
+        // 1. Amostragem
+        let amostra = match melhor {
+            Some((_, c_best)) if informado => match amostrar_na_elipse(map, &inicio, &objetivo, c_best, rng) {
+                Some(p) => p,
+                None => continue,
+            },
+            None if rng.gen_bool(cfg.prob_objetivo) => objetivo,
+            _ => sample_valid_point(map, rng),
+        };
+
+        // 2. Vértice mais próximo e passo em direção à amostra
+        let perto = (0..arvore.pontos.len())
+            .min_by(|&i, &j| arvore.pontos[i].dist(&amostra).total_cmp(&arvore.pontos[j].dist(&amostra)))
+            .unwrap();
+        let origem = arvore.pontos[perto];
+        let dist = origem.dist(&amostra);
+        if dist == 0.0 {
+            continue;
+        }
+        let t = (cfg.passo / dist).min(1.0);
+        let novo = Point { x: origem.x + t * (amostra.x - origem.x), y: origem.y + t * (amostra.y - origem.y) };
+        if map.is_path_colliding(&origem, &novo, passos_colisao(origem.dist(&novo))) {
+            continue;
+        }
+
+        // 3. Vizinhos dentro do raio do RRT* (que diminui conforme a árvore cresce)
+        let raio = raio_estrela(gamma, arvore.pontos.len() + 1).min(cfg.passo);
+        let livre = |a: &Point, b: &Point| !map.is_path_colliding(a, b, passos_colisao(a.dist(b)));
+        let vizinhos: Vec<usize> = (0..arvore.pontos.len())
+            .filter(|&i| arvore.pontos[i].dist(&novo) <= raio)
+            .collect();
+
+        // 4. Escolhe o pai que dá o menor custo até o novo vértice
+        let mut pai = perto;
+        let mut custo_pai = arvore.custo[perto] + origem.dist(&novo);
+        for &v in &vizinhos {
+            let custo = arvore.custo[v] + arvore.pontos[v].dist(&novo);
+            if custo < custo_pai && livre(&arvore.pontos[v], &novo) {
+                pai = v;
+                custo_pai = custo;
+            }
+        }
+        let idx = arvore.adicionar(novo, pai);
+
+        // 5. Rewiring: passa os vizinhos para o novo vértice se ficar mais barato
+        for &v in &vizinhos {
+            if v == pai {
+                continue;
+            }
+            let custo = arvore.custo[idx] + novo.dist(&arvore.pontos[v]);
+            if custo < arvore.custo[v] && livre(&novo, &arvore.pontos[v]) {
+                arvore.religar(v, idx);
+            }
+        }
+
+        // 6. Guarda o vértice se ele consegue ligar direto no destino
+        let dist_objetivo = novo.dist(&objetivo);
+        if dist_objetivo <= cfg.passo && livre(&novo, &objetivo) {
+            perto_objetivo.push(idx);
+        }
+
+        // 7. Melhor custo atual (os custos mudam com o rewiring, então é recalculado)
+        let atual = perto_objetivo
+            .iter()
+            .map(|&v| (v, arvore.custo[v] + arvore.pontos[v].dist(&objetivo)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((_, custo)) = atual {
+            if melhor.map_or(true, |(_, c)| custo < c) {
+                convergencia.push((iteracao, custo));
+            }
+        }
+        melhor = atual;
+    }
+
+    if let Some((_, custo)) = melhor {
+        if convergencia.last().map_or(true, |&(it, _)| it != cfg.max_iteracoes) {
+            convergencia.push((cfg.max_iteracoes, custo));
+        }
+    }
+
+    // Monta o Graph da árvore final e, se houver solução, adiciona o destino e o caminho
+    let mut graph = Graph::new();
+    for &p in &arvore.pontos {
+        graph.add_vertex(p);
+    }
+    for (v, pai) in arvore.pai.iter().enumerate() {
+        if let Some(p) = *pai {
+            graph.add_edge_dist(p, v);
+        }
+    }
+
+    let (caminho, custo) = match melhor {
+        Some((ultimo, custo)) => {
+            let fim = graph.add_vertex(objetivo);
+            graph.add_edge_dist(ultimo, fim);
+
+            let mut caminho = vec![fim, ultimo];
+            let mut atual = ultimo;
+            while let Some(p) = arvore.pai[atual] {
+                caminho.push(p);
+                atual = p;
+            }
+            caminho.reverse();
+            (Some(caminho), Some(custo))
+        }
+        None => (None, None),
+    };
+
+    Ok(ResultadoRrtEstrela { arvore: graph, caminho, custo, convergencia })
+}
+
+/// Salva o histórico do melhor custo em um CSV com cabeçalho 'iteracao,custo'
+pub fn salvar_convergencia_csv(resultado: &ResultadoRrtEstrela, file_path: &str) -> Result<(), Box<dyn Error>> {
+    let mut wtr = Writer::from_path(file_path)?;
+    wtr.write_record(&["iteracao", "custo"])?;
+    for &(iteracao, custo) in &resultado.convergencia {
+        wtr.write_record(&[iteracao.to_string(), format!("{:.4}", custo)])?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readmap::mapa_de_teste;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// Mapa 80x60 com uma parede grossa (x de 36 a 43) que só deixa passagem embaixo (y de 41 a 58)
+    fn mapa_com_parede() -> OccupancyMap {
+        mapa_de_teste(80, 60, |x, y| x == 0 || y == 0 || x == 79 || y == 59 || ((36..=43).contains(&x) && y <= 40))
+    }
+
+    const INICIO: Point = Point { x: 10.0, y: 10.0 };
+    const OBJETIVO: Point = Point { x: 70.0, y: 10.0 };
+    const CONFIG: ConfigRrtEstrela = ConfigRrtEstrela { passo: 8.0, max_iteracoes: 2000, prob_objetivo: 0.05 };
+
+    /// Confere as arestas da árvore, os extremos e o custo do caminho e que o histórico de custo nunca sobe
+    fn conferir_resultado(map: &OccupancyMap, resultado: &ResultadoRrtEstrela) {
+        let arvore = &resultado.arvore;
+        for e in arvore.get_undirected_edges() {
+            let (p, q) = (arvore.vertices[e.from], arvore.vertices[e.to]);
+            assert!(!map.is_path_colliding(&p, &q, passos_colisao(p.dist(&q))), "aresta {:?} -> {:?} colide", p, q);
+        }
+
+        let caminho = resultado.caminho.as_ref().expect("o planejador deveria achar um caminho");
+        assert_eq!(arvore.vertices[caminho[0]], INICIO);
+        assert_eq!(arvore.vertices[*caminho.last().unwrap()], OBJETIVO);
+        let comprimento: f64 = caminho.windows(2).map(|par| arvore.vertices[par[0]].dist(&arvore.vertices[par[1]])).sum();
+        assert!((comprimento - resultado.custo.unwrap()).abs() < 1e-6);
+        assert!(comprimento >= INICIO.dist(&OBJETIVO));
+
+        let convergencia = &resultado.convergencia;
+        assert!(convergencia.windows(2).all(|par| par[1].0 > par[0].0 && par[1].1 <= par[0].1), "{:?}", convergencia);
+        assert_eq!(convergencia.last(), Some(&(CONFIG.max_iteracoes, resultado.custo.unwrap())));
+    }
+
+    #[test]
+    fn rrt_estrela_contorna_a_parede() {
+        let map = mapa_com_parede();
+        for semente in 0..3 {
+            conferir_resultado(&map, &rrt_estrela_com_rng(&map, INICIO, OBJETIVO, &CONFIG, &mut StdRng::seed_from_u64(semente)).unwrap());
+        }
+    }
+
+    #[test]
+    fn informed_rrt_estrela_contorna_a_parede() {
+        let map = mapa_com_parede();
+        for semente in 0..3 {
+            conferir_resultado(&map, &informed_rrt_estrela_com_rng(&map, INICIO, OBJETIVO, &CONFIG, &mut StdRng::seed_from_u64(semente)).unwrap());
+        }
+    }
+
+    #[test]
+    fn informed_comeca_igual_e_termina_mais_barato() {
+        let map = mapa_com_parede();
+        let (mut soma_normal, mut soma_informado) = (0.0, 0.0);
+        for semente in 0..8 {
+            let normal = rrt_estrela_com_rng(&map, INICIO, OBJETIVO, &CONFIG, &mut StdRng::seed_from_u64(semente)).unwrap();
+            let informado = informed_rrt_estrela_com_rng(&map, INICIO, OBJETIVO, &CONFIG, &mut StdRng::seed_from_u64(semente)).unwrap();
+            // Até a primeira solução os dois sorteiam exatamente as mesmas amostras
+            assert_eq!(normal.convergencia[0], informado.convergencia[0]);
+            soma_normal += normal.custo.unwrap();
+            soma_informado += informado.custo.unwrap();
+        }
+        assert!(soma_informado < soma_normal, "informed {} x RRT* {}", soma_informado / 8.0, soma_normal / 8.0);
+    }
+
+    #[test]
+    fn mesma_semente_mesmo_resultado() {
+        let map = mapa_com_parede();
+        let rodar = || informed_rrt_estrela_com_rng(&map, INICIO, OBJETIVO, &CONFIG, &mut StdRng::seed_from_u64(5)).unwrap();
+        let (a, b) = (rodar(), rodar());
+        assert_eq!((a.custo, &a.caminho, &a.convergencia), (b.custo, &b.caminho, &b.convergencia));
+    }
+
+    #[test]
+    fn amostras_da_elipse_ficam_dentro_dela() {
+        let map = mapa_com_parede();
+        let (inicio, objetivo) = (Point { x: 10.0, y: 50.0 }, Point { x: 70.0, y: 50.0 });
+        let c_best = 70.0;
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..500 {
+            let p = amostrar_na_elipse(&map, &inicio, &objetivo, c_best, &mut rng).unwrap();
+            assert!(p.dist(&inicio) + p.dist(&objetivo) <= c_best + 1e-9, "{:?} fora da elipse", p);
+            assert!(!map.is_obstructed(&p));
+        }
+
+        // Sem espaço livre na elipse não há amostra (em vez de cair para o mapa todo)
+        let bloqueado = mapa_de_teste(80, 60, |x, _| (5..=75).contains(&x));
+        assert_eq!(amostrar_na_elipse(&bloqueado, &inicio, &objetivo, c_best, &mut rng), None);
+    }
+
+    #[test]
+    fn extremos_em_obstaculo_sao_erro() {
+        let map = mapa_com_parede();
+        let na_parede = Point { x: 40.0, y: 10.0 };
+        assert!(rrt_estrela(&map, na_parede, OBJETIVO, &CONFIG).is_err());
+        assert!(informed_rrt_estrela(&map, INICIO, na_parede, &CONFIG).is_err());
+    }
+}
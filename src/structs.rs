@@ -2,6 +2,8 @@
 
 #![allow(warnings)]
 
+use std::cmp::Ordering;
+use std::fmt;
 use std::hash::{Hash, Hasher};
 
 #[derive(Clone, Copy, Debug)]
@@ -32,27 +34,151 @@ impl Hash for Point {
     }
 }
 
+/// Polígono simples (por exemplo, um obstáculo), com os vértices em ordem ao longo da borda.
+/// O último vértice se liga ao primeiro; não precisa repetir o primeiro no final.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Poligono {
+    pub vertices: Vec<Point>,
+}
+
+impl Poligono {
+    /// Área com sinal (fórmula do laço): positiva se os vértices estão no sentido em que as curvas são para a esquerda
+    pub fn area_com_sinal(&self) -> f64 {
+        let n = self.vertices.len();
+        let mut soma = 0.0;
+        for i in 0..n {
+            let a = self.vertices[i];
+            let b = self.vertices[(i + 1) % n];
+            soma += a.x * b.y - b.x * a.y;
+        }
+        soma / 2.0
+    }
+
+    /// Retorna true se `p` está dentro do polígono (regra par-ímpar). Pontos exatamente na borda podem dar qualquer resultado.
+    pub fn contem(&self, p: &Point) -> bool {
+        let n = self.vertices.len();
+        let mut dentro = false;
+        let mut j = n.wrapping_sub(1);
+        for i in 0..n {
+            let a = self.vertices[i];
+            let b = self.vertices[j];
+            // A semi-reta horizontal saindo de p para a direita cruza a aresta (a, b)?
+            if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+                dentro = !dentro;
+            }
+            j = i;
+        }
+        dentro
+    }
+}
+
+/// Polígono com buracos: o contorno externo de um obstáculo e os contornos das regiões livres dentro dele.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoligonoComBuracos {
+    pub externo: Poligono,
+    pub buracos: Vec<Poligono>,
+}
+
+impl PoligonoComBuracos {
+    /// Retorna true se `p` está no obstáculo: dentro do contorno externo e fora de todos os buracos
+    pub fn contem(&self, p: &Point) -> bool {
+        self.externo.contem(p) && !self.buracos.iter().any(|b| b.contem(p))
+    }
+}
+
+// --- Peso das arestas ---
+
+/// Peso de uma aresta. Só aceita valores finitos, então tem ordem total
+/// e pode ser ordenado sem `partial_cmp(...).unwrap()`.
+#[derive(Clone, Copy, Debug)]
+pub struct Peso(f64);
+
+impl Peso {
+    /// Cria um peso, recusando NaN e infinitos
+    pub fn new(valor: f64) -> Result<Self, String> {
+        if valor.is_finite() {
+            // Soma 0.0 para transformar -0.0 em 0.0 e os dois serem o mesmo peso
+            Ok(Peso(valor + 0.0))
+        } else {
+            Err(format!("Peso de aresta inválido: {} (precisa ser finito).", valor))
+        }
+    }
+
+    /// Retorna o valor do peso
+    pub fn valor(&self) -> f64 {
+        self.0
+    }
+}
+
+impl PartialEq for Peso {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Peso {}
+
+impl Ord for Peso {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl PartialOrd for Peso {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for Peso {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl fmt::Display for Peso {
+    /// Repassa a formatação para o f64, então `{:.4}` funciona normalmente
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
 // --- Estruturas de Grafo Ponderado ---
 
 /// Representa uma aresta direcionada e ponderada no grafo.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Edge {
     /// O índice do vértice de destino na lista 'vertices' do grafo.
     pub to_idx: usize,
     /// O peso associado a esta aresta.
-    pub weight: f64,
+    pub weight: Peso,
 }
 
 /// Usado para retornar a lista de arestas pra dar sort,
 // A struct salva a informação do vértice de partida além do de ida
-#[derive(Debug, Clone)]
+// A ordem é por peso e, em empate, pelo par (from, to), então o sort é igual em qualquer execução/plataforma
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UndirEdge {
-    /// O indice do vértice de partida
+    /// O indice do vértice de partida (sempre o menor dos dois)
     pub from: usize,
-    /// O indice do vértice de destino
+    /// O indice do vértice de destino (sempre o maior dos dois)
     pub to: usize,
     /// O peso da aresta
-    pub weight: f64,
+    pub weight: Peso,
+}
+
+impl Ord for UndirEdge {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.weight.cmp(&other.weight)
+            .then_with(|| self.from.cmp(&other.from))
+            .then_with(|| self.to.cmp(&other.to))
+    }
+}
+
+impl PartialOrd for UndirEdge {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 
@@ -83,11 +209,31 @@ impl Graph {
 
     /// Adiciona uma aresta NÃO DIRECIONADA de 'from_idx' para 'to_idx', ou seja, adiciona para a ida e volta
     /// com um 'weight' (peso) especificado.
-    pub fn add_edge(&mut self, from_idx: usize, to_idx: usize, weight: f64) {
-        if from_idx < self.vertices.len() && to_idx < self.vertices.len() {
-            self.adj[from_idx].push(Edge { to_idx, weight });
-            self.adj[to_idx].push(Edge { to_idx: from_idx, weight });
+    /// Retorna erro se algum índice não existir ou se o peso não for finito (NaN ou infinito).
+    pub fn add_edge(&mut self, from_idx: usize, to_idx: usize, weight: f64) -> Result<(), String> {
+        let peso = Peso::new(weight)?;
+        self.add_edge_peso(from_idx, to_idx, peso)
+    }
+
+    /// Adiciona a aresta não direcionada (u, v) com o comprimento do segmento entre os dois vértices como peso.
+    /// Para os planejadores que ligam vértices já inseridos: entra em pânico se algum índice não existir ou se
+    /// algum dos vértices tiver coordenada não finita.
+    pub fn add_edge_dist(&mut self, u: usize, v: usize) {
+        let dist = self.vertices[u].dist(&self.vertices[v]);
+        self.add_edge(u, v, dist).expect("A distância entre dois vértices finitos é sempre finita.");
+    }
+
+    /// Igual ao `add_edge`, mas recebe um `Peso` já validado (por exemplo, copiado de outro grafo).
+    pub fn add_edge_peso(&mut self, from_idx: usize, to_idx: usize, weight: Peso) -> Result<(), String> {
+        if from_idx >= self.vertices.len() || to_idx >= self.vertices.len() {
+            return Err(format!(
+                "Aresta ({}, {}) inválida: o grafo tem {} vértices.",
+                from_idx, to_idx, self.vertices.len()
+            ));
         }
+        self.adj[from_idx].push(Edge { to_idx, weight });
+        self.adj[to_idx].push(Edge { to_idx: from_idx, weight });
+        Ok(())
     }
     
     /// Retorna a coordenada de um vértice pelo seu índice.
@@ -95,6 +241,18 @@ impl Graph {
         self.vertices.get(idx)
     }
 
+    /// Remove a aresta NÃO DIRECIONADA entre 'a' e 'b' (ida e volta, incluindo duplicatas).
+    /// Retorna true se alguma aresta foi removida.
+    pub fn remove_edge(&mut self, a: usize, b: usize) -> bool {
+        if a >= self.adj.len() || b >= self.adj.len() {
+            return false;
+        }
+        let antes = self.adj[a].len() + self.adj[b].len();
+        self.adj[a].retain(|e| e.to_idx != b);
+        self.adj[b].retain(|e| e.to_idx != a);
+        self.adj[a].len() + self.adj[b].len() < antes
+    }
+
     /// Retorna a lista de arestas (vizinhos e pesos) de um vértice.
     pub fn get_neighbors(&self, idx: usize) -> Option<&Vec<Edge>> {
         self.adj.get(idx)
@@ -121,10 +279,10 @@ impl Graph {
 
                 /// Se a aresta já tiver sido vista esse if falha.
                 if visto.insert(key) {
-                    /// Cria um UndirEdge com as informações de partida, ida e peso
+                    /// Cria um UndirEdge com as informações de partida, ida e peso (from é sempre o menor índice)
                     edges.push(UndirEdge{
-                        from: from_idx,
-                        to: edge.to_idx,
+                        from: key.0,
+                        to: key.1,
                         weight: edge.weight,
                     });
                 }
@@ -134,3 +292,60 @@ impl Graph {
         edges
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peso_recusa_nan_e_infinito() {
+        for invalido in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(Peso::new(invalido).is_err(), "aceitou {}", invalido);
+        }
+        assert_eq!(Peso::new(2.5).unwrap().valor(), 2.5);
+        assert_eq!(Peso::new(-0.0).unwrap(), Peso::new(0.0).unwrap());
+    }
+
+    #[test]
+    fn peso_tem_ordem_total() {
+        let mut pesos: Vec<Peso> = [3.0, -1.0, 0.0, 1e-300, -0.0, 2.0].iter().map(|&v| Peso::new(v).unwrap()).collect();
+        pesos.sort();
+        let valores: Vec<f64> = pesos.iter().map(|p| p.valor()).collect();
+        assert_eq!(valores, vec![-1.0, 0.0, 0.0, 1e-300, 2.0, 3.0]);
+        assert_eq!(pesos.iter().max().unwrap().valor(), 3.0);
+        assert!(Peso::new(1.0).unwrap() < Peso::new(1.0 + f64::EPSILON).unwrap());
+    }
+
+    #[test]
+    fn undir_edge_desempata_por_indices() {
+        let aresta = |from, to, peso| UndirEdge { from, to, weight: Peso::new(peso).unwrap() };
+        let mut arestas = vec![aresta(2, 3, 1.0), aresta(0, 5, 2.0), aresta(1, 4, 1.0), aresta(1, 2, 1.0), aresta(0, 1, 3.0)];
+        arestas.sort();
+        let ordem: Vec<(usize, usize)> = arestas.iter().map(|e| (e.from, e.to)).collect();
+        assert_eq!(ordem, vec![(1, 2), (1, 4), (2, 3), (0, 5), (0, 1)]);
+    }
+
+    #[test]
+    fn add_edge_recusa_peso_e_indice_invalidos() {
+        let mut gr = Graph::new();
+        gr.add_vertex(Point { x: 0.0, y: 0.0 });
+        gr.add_vertex(Point { x: 1.0, y: 0.0 });
+        assert!(gr.add_edge(0, 1, f64::NAN).is_err());
+        assert!(gr.add_edge(0, 2, 1.0).is_err());
+        assert!(gr.adj.iter().all(|vizinhos| vizinhos.is_empty()));
+
+        gr.add_edge(1, 0, 1.0).unwrap();
+        let arestas = gr.get_undirected_edges();
+        assert_eq!((arestas[0].from, arestas[0].to), (0, 1), "from é sempre o menor índice");
+    }
+
+    #[test]
+    fn add_edge_dist_usa_o_comprimento_do_segmento() {
+        let mut gr = Graph::new();
+        gr.add_vertex(Point { x: 1.0, y: 1.0 });
+        gr.add_vertex(Point { x: 4.0, y: 5.0 });
+        gr.add_edge_dist(1, 0);
+        assert_eq!(gr.adj[0][0].weight.valor(), 5.0);
+        assert_eq!(gr.adj[1][0].weight.valor(), 5.0);
+    }
+}
@@ -0,0 +1,224 @@
+
+//! #  Validação das invariantes de um grafo
+//! Como `Graph` expõe `vertices` e `adj` como campos públicos, é possível montar grafos inconsistentes.
+//! `validate` confere o grafo inteiro e retorna todas as violações encontradas, com os índices envolvidos.
+//! Pesos NaN ou infinitos não entram na lista: o `Peso` já os recusa na construção.
+
+#![allow(warnings)]
+
+use crate::structs::{Graph, Peso};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Uma invariante do grafo que foi violada
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violacao {
+    /// `adj` e `vertices` têm tamanhos diferentes
+    TamanhoInconsistente { vertices: usize, listas_adj: usize },
+    /// O vértice tem coordenada NaN ou infinita
+    VerticeNaoFinito { vertice: usize },
+    /// A aresta aponta para um vértice que não existe
+    ArestaForaDoIntervalo { from: usize, to: usize },
+    /// O vértice tem uma aresta para ele mesmo
+    AutoLaco { vertice: usize },
+    /// Existe `from -> to` com esse peso, mas não existe a volta `to -> from` com o mesmo peso
+    ArestaAssimetrica { from: usize, to: usize, peso: Peso },
+    /// A mesma aresta aparece mais de uma vez
+    ArestaDuplicada { from: usize, to: usize, vezes: usize },
+    /// A aresta é mais comprida que o raio de conexão do PRM
+    ArestaMaiorQueRaio { from: usize, to: usize, comprimento: f64, raio: f64 },
+}
+
+impl fmt::Display for Violacao {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violacao::TamanhoInconsistente { vertices, listas_adj } =>
+                write!(f, "O grafo tem {} vértices mas {} listas de adjacência.", vertices, listas_adj),
+            Violacao::VerticeNaoFinito { vertice } =>
+                write!(f, "Vértice {} tem coordenada não finita.", vertice),
+            Violacao::ArestaForaDoIntervalo { from, to } =>
+                write!(f, "Aresta ({}, {}) aponta para um vértice inexistente.", from, to),
+            Violacao::AutoLaco { vertice } =>
+                write!(f, "Vértice {} tem uma aresta para ele mesmo.", vertice),
+            Violacao::ArestaAssimetrica { from, to, peso } =>
+                write!(f, "Aresta ({}, {}) de peso {:.4} não tem a volta ({}, {}).", from, to, peso, to, from),
+            Violacao::ArestaDuplicada { from, to, vezes } =>
+                write!(f, "Aresta ({}, {}) aparece {} vezes.", from, to, vezes),
+            Violacao::ArestaMaiorQueRaio { from, to, comprimento, raio } =>
+                write!(f, "Aresta ({}, {}) tem comprimento {:.4}, maior que o raio {:.4}.", from, to, comprimento, raio),
+        }
+    }
+}
+
+/// Confere todas as invariantes do grafo e retorna a lista de violações encontradas.
+/// Se `raio_maximo` for informado, também confere se nenhuma aresta é mais comprida que ele
+/// (use o `connection_radius` do PRM; para grafos lidos de arquivo normalmente é `None`).
+pub fn validate(gr: &Graph, raio_maximo: Option<f64>) -> Result<(), Vec<Violacao>> {
+    let mut violacoes = Vec::new();
+    let n = gr.vertices.len();
+
+    if gr.adj.len() != n {
+        violacoes.push(Violacao::TamanhoInconsistente { vertices: n, listas_adj: gr.adj.len() });
+    }
+
+    for (i, p) in gr.vertices.iter().enumerate() {
+        if !p.x.is_finite() || !p.y.is_finite() {
+            violacoes.push(Violacao::VerticeNaoFinito { vertice: i });
+        }
+    }
+
+    /// Conta cada aresta direcionada (from, to, peso) para comparar ida e volta depois
+    let mut contagem: HashMap<(usize, usize, Peso), usize> = HashMap::new();
+
+    for (from, neighbors) in gr.adj.iter().enumerate() {
+        /// Quantas vezes cada vizinho aparece na lista deste vértice
+        let mut vezes_por_vizinho: HashMap<usize, usize> = HashMap::new();
+        let mut tem_auto_laco = false;
+
+        for edge in neighbors {
+            let to = edge.to_idx;
+
+            if to >= n {
+                violacoes.push(Violacao::ArestaForaDoIntervalo { from, to });
+                continue;
+            }
+            if to == from {
+                tem_auto_laco = true;
+                continue;
+            }
+            if let Some(raio) = raio_maximo {
+                // Só informa cada aresta uma vez (pelo lado de menor índice, que sempre existe em `vertices`)
+                if from < to && gr.vertices[from].dist(&gr.vertices[to]) > raio * (1.0 + 1e-9) {
+                    let comprimento = gr.vertices[from].dist(&gr.vertices[to]);
+                    violacoes.push(Violacao::ArestaMaiorQueRaio { from, to, comprimento, raio });
+                }
+            }
+
+            *vezes_por_vizinho.entry(to).or_insert(0) += 1;
+            *contagem.entry((from, to, edge.weight)).or_insert(0) += 1;
+        }
+
+        if tem_auto_laco {
+            violacoes.push(Violacao::AutoLaco { vertice: from });
+        }
+
+        let mut duplicadas: Vec<(usize, usize)> = vezes_por_vizinho.into_iter().filter(|&(to, vezes)| vezes > 1 && from < to).collect();
+        duplicadas.sort();
+        for (to, vezes) in duplicadas {
+            violacoes.push(Violacao::ArestaDuplicada { from, to, vezes });
+        }
+    }
+
+    /// Cada ida precisa de uma volta com o mesmo peso (na mesma quantidade)
+    let mut assimetricas: Vec<(usize, usize, Peso)> = contagem
+        .iter()
+        .filter(|&(&(from, to, peso), &vezes)| contagem.get(&(to, from, peso)).copied().unwrap_or(0) < vezes)
+        .map(|(&chave, _)| chave)
+        .collect();
+    assimetricas.sort();
+    for (from, to, peso) in assimetricas {
+        violacoes.push(Violacao::ArestaAssimetrica { from, to, peso });
+    }
+
+    if violacoes.is_empty() {
+        Ok(())
+    } else {
+        Err(violacoes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::{Edge, Point};
+
+    /// Grafo com os vértices (0, 0), (3, 4) e (6, 0), sem arestas
+    fn tres_vertices() -> Graph {
+        let mut gr = Graph::new();
+        for (x, y) in [(0.0, 0.0), (3.0, 4.0), (6.0, 0.0)] {
+            gr.add_vertex(Point { x, y });
+        }
+        gr
+    }
+
+    fn peso(valor: f64) -> Peso {
+        Peso::new(valor).unwrap()
+    }
+
+    #[test]
+    fn grafo_valido() {
+        let mut gr = tres_vertices();
+        gr.add_edge(0, 1, 5.0).unwrap();
+        gr.add_edge(1, 2, 5.0).unwrap();
+        assert_eq!(validate(&gr, None), Ok(()));
+        assert_eq!(validate(&gr, Some(5.0)), Ok(()));
+        assert_eq!(validate(&Graph::new(), Some(1.0)), Ok(()));
+    }
+
+    #[test]
+    fn tamanho_inconsistente_e_vertice_nao_finito() {
+        let mut gr = tres_vertices();
+        gr.adj.push(Vec::new());
+        assert_eq!(validate(&gr, None), Err(vec![Violacao::TamanhoInconsistente { vertices: 3, listas_adj: 4 }]));
+
+        let mut gr = tres_vertices();
+        gr.vertices[2].y = f64::NAN;
+        assert_eq!(validate(&gr, None), Err(vec![Violacao::VerticeNaoFinito { vertice: 2 }]));
+    }
+
+    #[test]
+    fn aresta_fora_do_intervalo() {
+        let mut gr = tres_vertices();
+        gr.adj[1].push(Edge { to_idx: 7, weight: peso(1.0) });
+        assert_eq!(validate(&gr, None), Err(vec![Violacao::ArestaForaDoIntervalo { from: 1, to: 7 }]));
+    }
+
+    #[test]
+    fn auto_laco() {
+        let mut gr = tres_vertices();
+        gr.add_edge(2, 2, 1.0).unwrap();
+        assert_eq!(validate(&gr, None), Err(vec![Violacao::AutoLaco { vertice: 2 }]));
+    }
+
+    #[test]
+    fn aresta_assimetrica() {
+        // Só a ida
+        let mut gr = tres_vertices();
+        gr.adj[0].push(Edge { to_idx: 2, weight: peso(6.0) });
+        assert_eq!(validate(&gr, None), Err(vec![Violacao::ArestaAssimetrica { from: 0, to: 2, peso: peso(6.0) }]));
+
+        // Ida e volta com pesos diferentes: as duas direções são informadas
+        let mut gr = tres_vertices();
+        gr.adj[0].push(Edge { to_idx: 1, weight: peso(5.0) });
+        gr.adj[1].push(Edge { to_idx: 0, weight: peso(4.0) });
+        assert_eq!(
+            validate(&gr, None),
+            Err(vec![
+                Violacao::ArestaAssimetrica { from: 0, to: 1, peso: peso(5.0) },
+                Violacao::ArestaAssimetrica { from: 1, to: 0, peso: peso(4.0) },
+            ])
+        );
+    }
+
+    #[test]
+    fn aresta_duplicada() {
+        let mut gr = tres_vertices();
+        for _ in 0..3 {
+            gr.add_edge(2, 1, 5.0).unwrap();
+        }
+        assert_eq!(validate(&gr, None), Err(vec![Violacao::ArestaDuplicada { from: 1, to: 2, vezes: 3 }]));
+    }
+
+    #[test]
+    fn aresta_maior_que_raio() {
+        let mut gr = tres_vertices();
+        gr.add_edge(0, 1, 5.0).unwrap();
+        gr.add_edge(2, 0, 6.0).unwrap();
+        assert_eq!(
+            validate(&gr, Some(5.5)),
+            Err(vec![Violacao::ArestaMaiorQueRaio { from: 0, to: 2, comprimento: 6.0, raio: 5.5 }])
+        );
+        // Sem raio a aresta comprida é aceita
+        assert_eq!(validate(&gr, None), Ok(()));
+    }
+}
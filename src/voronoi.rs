@@ -0,0 +1,563 @@
+
+//! #  Roadmap pelo diagrama de Voronoi generalizado (eixo medial do espaço livre)
+//! O eixo medial é o conjunto de pontos livres equidistantes de dois ou mais obstáculos, ou seja, o roadmap de
+//! folga máxima. Ele é aproximado pelo afinamento (Zhang–Suen) da máscara livre, e a transformada de distância
+//! dá a folga de cada ponto. Os pixels do esqueleto viram um `Graph`: junções e pontas são vértices e cada
+//! trecho entre eles vira uma aresta com o comprimento em pixels do trecho.
+//! ## Output esperado
+//! Um `RoadmapVoronoi` e, para uma consulta, o grafo com o início e o destino ligados por segmentos livres
+//! e o caminho nele (pode ser salvo com `path_export`).
+
+#![allow(warnings)]
+
+use crate::readmap::OccupancyMap;
+use crate::structs::{Graph, Point};
+use crate::prm_generator::passos_colisao;
+use crate::dijkstra::dijkstra_path;
+use std::collections::HashSet;
+
+/// Vizinhos na ordem N, NE, E, SE, S, SW, W, NW (sentido horário na imagem, y para baixo)
+const VIZINHOS: [(i64, i64); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+/// Roadmap do eixo medial
+#[derive(Debug)]
+pub struct RoadmapVoronoi {
+    pub graph: Graph,
+    /// Distância (em pixels) de cada vértice até o obstáculo mais próximo
+    pub folga: Vec<f64>,
+}
+
+/// Transformada de distância euclidiana exata (Felzenszwalb–Huttenlocher): para cada pixel da imagem,
+/// a distância em pixels até o obstáculo mais próximo (0 nos obstáculos, infinito se não houver obstáculo).
+/// O vetor é indexado por `y * largura + x`.
+pub fn transformada_distancia(map: &OccupancyMap) -> Vec<f64> {
+    let (largura, altura) = map.image.dimensions();
+    let (largura, altura) = (largura as usize, altura as usize);
+
+    let mut d2: Vec<f64> = (0..largura * altura)
+        .map(|i| if map.pixel_ocupado((i % largura) as u32, (i / largura) as u32) { 0.0 } else { f64::INFINITY })
+        .collect();
+
+    // Passa a transformada 1D nas colunas e depois nas linhas
+    let mut f = vec![0.0; largura.max(altura)];
+    for x in 0..largura {
+        for y in 0..altura {
+            f[y] = d2[y * largura + x];
+        }
+        let coluna = distancia_1d(&f[..altura]);
+        for y in 0..altura {
+            d2[y * largura + x] = coluna[y];
+        }
+    }
+    for y in 0..altura {
+        let linha = distancia_1d(&d2[y * largura..(y + 1) * largura]);
+        d2[y * largura..(y + 1) * largura].copy_from_slice(&linha);
+    }
+
+    d2.into_iter().map(f64::sqrt).collect()
+}
+
+/// Transformada de distância ao quadrado em 1D pelo envelope inferior de parábolas
+fn distancia_1d(f: &[f64]) -> Vec<f64> {
+    let n = f.len();
+    let mut d = vec![f64::INFINITY; n];
+    // Vértices das parábolas do envelope e os limites entre elas
+    let mut v = vec![0usize; n];
+    let mut z = vec![0.0f64; n + 1];
+    let mut k: isize = -1;
+
+    for q in 0..n {
+        if f[q].is_infinite() {
+            continue;
+        }
+        loop {
+            if k < 0 {
+                k = 0;
+                v[0] = q;
+                z[0] = f64::NEG_INFINITY;
+                z[1] = f64::INFINITY;
+                break;
+            }
+            let p = v[k as usize];
+            let s = ((f[q] + (q * q) as f64) - (f[p] + (p * p) as f64)) / (2.0 * (q as f64 - p as f64));
+            if s <= z[k as usize] {
+                k -= 1;
+            } else {
+                k += 1;
+                v[k as usize] = q;
+                z[k as usize] = s;
+                z[k as usize + 1] = f64::INFINITY;
+                break;
+            }
+        }
+    }
+    if k < 0 {
+        return d;
+    }
+
+    let mut k = 0usize;
+    for q in 0..n {
+        while z[k + 1] < q as f64 {
+            k += 1;
+        }
+        let p = v[k];
+        d[q] = (q as f64 - p as f64).powi(2) + f[p];
+    }
+    d
+}
+
+/// Máscara booleana da imagem com acesso seguro fora dos limites (fora conta como false)
+struct Mascara {
+    largura: usize,
+    altura: usize,
+    pixels: Vec<bool>,
+}
+
+impl Mascara {
+    fn get(&self, x: i64, y: i64) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.largura && (y as usize) < self.altura
+            && self.pixels[y as usize * self.largura + x as usize]
+    }
+
+    /// Vizinhos na ordem de `VIZINHOS`
+    fn vizinhanca(&self, x: i64, y: i64) -> [bool; 8] {
+        let mut n = [false; 8];
+        for (i, &(dx, dy)) in VIZINHOS.iter().enumerate() {
+            n[i] = self.get(x + dx, y + dy);
+        }
+        n
+    }
+
+    fn grau(&self, x: i64, y: i64) -> usize {
+        self.vizinhanca(x, y).iter().filter(|&&b| b).count()
+    }
+}
+
+/// Afinamento de Zhang–Suen: remove pixels da borda em duas sub-iterações até sobrar um esqueleto
+fn zhang_suen(mascara: &mut Mascara) {
+    let mut candidatos: Vec<(i64, i64)> = (0..mascara.largura * mascara.altura)
+        .filter(|&i| mascara.pixels[i])
+        .map(|i| ((i % mascara.largura) as i64, (i / mascara.largura) as i64))
+        .collect();
+
+    loop {
+        let mut mudou = false;
+        for passo in 0..2 {
+            let remover: Vec<(i64, i64)> = candidatos
+                .iter()
+                .copied()
+                .filter(|&(x, y)| {
+                    let n = mascara.vizinhanca(x, y);
+                    let b = n.iter().filter(|&&v| v).count();
+                    // Transições 0 -> 1 na sequência circular N, NE, ..., NW
+                    let a = (0..8).filter(|&i| !n[i] && n[(i + 1) % 8]).count();
+                    let (p2, p4, p6, p8) = (n[0], n[2], n[4], n[6]);
+                    let condicao = if passo == 0 {
+                        !(p2 && p4 && p6) && !(p4 && p6 && p8)
+                    } else {
+                        !(p2 && p4 && p8) && !(p2 && p6 && p8)
+                    };
+                    (2..=6).contains(&b) && a == 1 && condicao
+                })
+                .collect();
+
+            for &(x, y) in &remover {
+                mascara.pixels[y as usize * mascara.largura + x as usize] = false;
+            }
+            mudou |= !remover.is_empty();
+        }
+        candidatos.retain(|&(x, y)| mascara.get(x, y));
+        if !mudou {
+            break;
+        }
+    }
+}
+
+/// Quantidade de componentes (vizinhança de 8) formadas pelos vizinhos ligados de um pixel
+fn componentes_vizinhos(n: &[bool; 8]) -> usize {
+    let mut pai: [usize; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+    fn raiz(pai: &mut [usize; 8], mut i: usize) -> usize {
+        while pai[i] != i {
+            i = pai[i];
+        }
+        i
+    }
+    for i in 0..8 {
+        // Posições seguidas no anel se tocam; os vizinhos N, E, S, W também tocam o próximo deles (ex.: N e E)
+        let mut ligados = vec![(i + 1) % 8];
+        if i % 2 == 0 {
+            ligados.push((i + 2) % 8);
+        }
+        for j in ligados {
+            if n[i] && n[j] {
+                let (a, b) = (raiz(&mut pai, i), raiz(&mut pai, j));
+                pai[a] = b;
+            }
+        }
+    }
+    (0..8).filter(|&i| n[i] && raiz(&mut pai, i) == i).count()
+}
+
+/// Pixel de "escada": encosta no fundo, os vizinhos formam uma única componente (removê-lo não muda a
+/// conectividade) e não é a ponta de um ramo. Ponta é o pixel de grau 1 ou de grau 2 com os dois vizinhos
+/// seguidos no anel (lado a lado, como no fim de uma escada); removê-la encurtaria o ramo.
+fn eh_escada(mascara: &Mascara, x: i64, y: i64) -> bool {
+    let n = mascara.vizinhanca(x, y);
+    let grau = n.iter().filter(|&&v| v).count();
+    let ponta = grau == 1 || (grau == 2 && (0..8).any(|i| n[i] && n[(i + 1) % 8]));
+    let toca_fundo = !n[0] || !n[2] || !n[4] || !n[6];
+    grau >= 2 && !ponta && toca_fundo && componentes_vizinhos(&n) == 1
+}
+
+/// Remove os pixels de "escada" que o Zhang–Suen deixa, para que todo pixel de grau 2 seja um pixel comum de
+/// um trecho, com os dois vizinhos não encostados. Os candidatos são marcados no esqueleto original e só
+/// depois removidos, conferindo de novo cada um na hora (dois candidatos vizinhos não podem sair juntos);
+/// um pixel que só virou escada por causa de uma remoção fica. As pontas nunca saem, então o ramo não é comido.
+fn limpar_escadas(mascara: &mut Mascara) {
+    let candidatos: Vec<(i64, i64)> = (0..mascara.altura as i64)
+        .flat_map(|y| (0..mascara.largura as i64).map(move |x| (x, y)))
+        .filter(|&(x, y)| mascara.get(x, y) && eh_escada(mascara, x, y))
+        .collect();
+    for (x, y) in candidatos {
+        if eh_escada(mascara, x, y) {
+            mascara.pixels[y as usize * mascara.largura + x as usize] = false;
+        }
+    }
+}
+
+/// Monta o roadmap do eixo medial do espaço livre. Pixels do esqueleto com folga menor que `folga_minima`
+/// (em pixels) são descartados, o que corta passagens mais estreitas que 2 * `folga_minima`.
+/// Ciclos isolados e trechos paralelos entre as mesmas junções ganham vértices intermediários
+/// para o grafo não ter arestas duplicadas.
+pub fn roadmap_voronoi(map: &OccupancyMap, folga_minima: f64) -> RoadmapVoronoi {
+    let (largura, altura) = map.image.dimensions();
+    let (largura, altura) = (largura as usize, altura as usize);
+    let distancia = transformada_distancia(map);
+
+    // 1. Esqueleto do espaço livre
+    let mut esqueleto = Mascara {
+        largura,
+        altura,
+        pixels: (0..largura * altura).map(|i| distancia[i] > 0.0).collect(),
+    };
+    zhang_suen(&mut esqueleto);
+    limpar_escadas(&mut esqueleto);
+    for i in 0..largura * altura {
+        if distancia[i] < folga_minima {
+            esqueleto.pixels[i] = false;
+        }
+    }
+
+    // 2. Vértices: pixels do esqueleto com grau diferente de 2 (pontas e junções)
+    let mut graph = Graph::new();
+    let mut folga = Vec::new();
+    let mut vertice_do_pixel: Vec<Option<usize>> = vec![None; largura * altura];
+    let mut adicionar_vertice = |graph: &mut Graph, folga: &mut Vec<f64>, vertice_do_pixel: &mut Vec<Option<usize>>, x: i64, y: i64| {
+        let i = y as usize * largura + x as usize;
+        let idx = graph.add_vertex(Point { x: x as f64, y: y as f64 });
+        folga.push(distancia[i]);
+        vertice_do_pixel[i] = Some(idx);
+        idx
+    };
+    for y in 0..altura as i64 {
+        for x in 0..largura as i64 {
+            if esqueleto.get(x, y) && esqueleto.grau(x, y) != 2 {
+                adicionar_vertice(&mut graph, &mut folga, &mut vertice_do_pixel, x, y);
+            }
+        }
+    }
+
+    // 3. Arestas: anda de cada vértice por cada vizinho até chegar em outro vértice
+    let mut visitado = vec![false; largura * altura];
+    let mut ligacoes_diretas: HashSet<(usize, usize)> = HashSet::new();
+    let mut pendentes: Vec<(i64, i64)> = graph.vertices.iter().map(|p| (p.x as i64, p.y as i64)).collect();
+
+    // Pixels de grau 2 que sobrarem sem visita formam ciclos isolados; cada um ganha um vértice e é percorrido
+    let mut proximo_ciclo = 0usize;
+    loop {
+        while let Some((x, y)) = pendentes.pop() {
+            let a = vertice_do_pixel[y as usize * largura + x as usize].unwrap();
+            for &(dx, dy) in &VIZINHOS {
+                let (mut cx, mut cy) = (x + dx, y + dy);
+                if !esqueleto.get(cx, cy) {
+                    continue;
+                }
+                let ic = cy as usize * largura + cx as usize;
+
+                if let Some(b) = vertice_do_pixel[ic] {
+                    // Dois vértices vizinhos: aresta direta (uma vez só)
+                    if ligacoes_diretas.insert((a.min(b), a.max(b))) && a != b {
+                        graph.add_edge_dist(a, b);
+                    }
+                    continue;
+                }
+                if visitado[ic] {
+                    continue;
+                }
+
+                // Segue o trecho de pixels de grau 2 guardando os pixels e o comprimento acumulado
+                let mut trecho = vec![(x, y)];
+                let (mut px, mut py) = (x, y);
+                let b = loop {
+                    visitado[cy as usize * largura + cx as usize] = true;
+                    trecho.push((cx, cy));
+                    if let Some(b) = vertice_do_pixel[cy as usize * largura + cx as usize] {
+                        break b;
+                    }
+                    let seguinte = VIZINHOS.iter()
+                        .map(|&(dx, dy)| (cx + dx, cy + dy))
+                        .find(|&(nx, ny)| esqueleto.get(nx, ny) && (nx, ny) != (px, py)
+                            && (vertice_do_pixel[ny as usize * largura + nx as usize].is_some()
+                                || !visitado[ny as usize * largura + nx as usize]));
+                    match seguinte {
+                        Some((nx, ny)) => {
+                            (px, py) = (cx, cy);
+                            (cx, cy) = (nx, ny);
+                        }
+                        // Trecho que termina sem vértice (não deveria acontecer): o último pixel vira vértice
+                        None => break adicionar_vertice(&mut graph, &mut folga, &mut vertice_do_pixel, cx, cy),
+                    }
+                };
+                adicionar_trecho(&mut graph, &mut folga, &distancia, largura, a, b, &trecho);
+            }
+        }
+
+        // Procura um ciclo isolado ainda não percorrido
+        while proximo_ciclo < largura * altura
+            && !(esqueleto.pixels[proximo_ciclo] && !visitado[proximo_ciclo] && vertice_do_pixel[proximo_ciclo].is_none())
+        {
+            proximo_ciclo += 1;
+        }
+        if proximo_ciclo >= largura * altura {
+            break;
+        }
+        let (x, y) = ((proximo_ciclo % largura) as i64, (proximo_ciclo / largura) as i64);
+        adicionar_vertice(&mut graph, &mut folga, &mut vertice_do_pixel, x, y);
+        visitado[proximo_ciclo] = true;
+        pendentes.push((x, y));
+    }
+
+    RoadmapVoronoi { graph, folga }
+}
+
+/// Comprimento de um trecho de pixels (passos de 1 ou √2)
+fn comprimento(trecho: &[(i64, i64)]) -> f64 {
+    trecho.windows(2).map(|p| (((p[1].0 - p[0].0).pow(2) + (p[1].1 - p[0].1).pow(2)) as f64).sqrt()).sum()
+}
+
+/// Adiciona o trecho de `a` até `b` como aresta. Se `a == b` (laço) ou se já existe aresta entre os dois
+/// (trecho paralelo), o trecho é dividido com vértices intermediários para não repetir arestas.
+fn adicionar_trecho(graph: &mut Graph, folga: &mut Vec<f64>, distancia: &[f64], largura: usize, a: usize, b: usize, trecho: &[(i64, i64)]) {
+    let ja_existe = graph.adj[a].iter().any(|e| e.to_idx == b);
+    let partes = if a == b { 3 } else if ja_existe { 2 } else { 1 };
+
+    // Índices do trecho onde ficam os vértices intermediários
+    let mut cortes: Vec<usize> = (1..partes).map(|k| k * (trecho.len() - 1) / partes).collect();
+    cortes.dedup();
+    cortes.retain(|&c| c > 0 && c < trecho.len() - 1);
+
+    let mut anterior = a;
+    let mut inicio = 0;
+    for &c in cortes.iter() {
+        let (x, y) = trecho[c];
+        let m = graph.add_vertex(Point { x: x as f64, y: y as f64 });
+        folga.push(distancia[y as usize * largura + x as usize]);
+        graph.add_edge(anterior, m, comprimento(&trecho[inicio..=c])).expect("Comprimento de trecho é finito.");
+        anterior = m;
+        inicio = c;
+    }
+    // Laços muito curtos que não dá para dividir são descartados
+    if anterior != b && !(anterior == a && (a == b || ja_existe)) {
+        graph.add_edge(anterior, b, comprimento(&trecho[inicio..])).expect("Comprimento de trecho é finito.");
+    }
+}
+
+/// Liga o ponto `p` ao vértice mais próximo do grafo que ele enxerga (segmento sem colisão).
+/// Retorna o índice do novo vértice.
+pub fn conectar_ao_roadmap(graph: &mut Graph, map: &OccupancyMap, p: Point) -> Result<usize, String> {
+    if map.is_obstructed(&p) {
+        return Err("O ponto está em um obstáculo.".to_string());
+    }
+
+    let mut candidatos: Vec<(f64, usize)> = graph.vertices.iter().enumerate().map(|(i, v)| (v.dist(&p), i)).collect();
+    candidatos.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+    for (dist, i) in candidatos {
+        let v = graph.vertices[i];
+        if !map.is_path_colliding(&p, &v, passos_colisao(dist)) {
+            let idx = graph.add_vertex(p);
+            graph.add_edge_dist(idx, i);
+            return Ok(idx);
+        }
+    }
+    Err("Nenhum vértice do roadmap é visível a partir do ponto.".to_string())
+}
+
+/// Consulta no roadmap: liga `inicio` e `fim` ao roadmap e procura o menor caminho.
+/// Retorna o grafo com os dois pontos adicionados e o caminho (índices nesse grafo).
+pub fn caminho_voronoi(roadmap: &RoadmapVoronoi, map: &OccupancyMap, inicio: Point, fim: Point) -> Result<(Graph, Vec<usize>), String> {
+    let mut graph = Graph::new();
+    for &v in &roadmap.graph.vertices {
+        graph.add_vertex(v);
+    }
+    for edge in roadmap.graph.get_undirected_edges() {
+        graph.add_edge_peso(edge.from, edge.to, edge.weight)?;
+    }
+
+    let i = conectar_ao_roadmap(&mut graph, map, inicio)?;
+    let f = conectar_ao_roadmap(&mut graph, map, fim)?;
+    match dijkstra_path(&graph, i, f) {
+        Some(path) => Ok((graph, path)),
+        None => Err("O início e o destino estão em partes desconexas do roadmap.".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readmap::mapa_de_teste;
+    use crate::validacao::validate;
+
+    /// Corredor horizontal livre de y = 10 a y = 19 (eixo em y = 14.5), fechado nas pontas em x = 0 e x = 79
+    fn corredor() -> OccupancyMap {
+        mapa_de_teste(80, 30, |x, y| x == 0 || x == 79 || y < 10 || y > 19)
+    }
+
+    #[test]
+    fn esqueleto_do_corredor_fica_no_eixo() {
+        let map = corredor();
+        let roadmap = roadmap_voronoi(&map, 2.0);
+        assert!(!roadmap.graph.vertices.is_empty());
+        assert_eq!(roadmap.folga.len(), roadmap.graph.vertices.len());
+
+        for (v, &folga) in roadmap.graph.vertices.iter().zip(&roadmap.folga) {
+            assert!(folga >= 2.0, "vértice {:?} com folga {}", v, folga);
+            // Longe das pontas o esqueleto é a linha do meio
+            if v.x > 12.0 && v.x < 67.0 {
+                assert!((v.y - 14.5).abs() <= 0.5, "vértice {:?} fora do eixo", v);
+            }
+        }
+        assert!(validate(&roadmap.graph, None).is_ok());
+    }
+
+    #[test]
+    fn caminho_liga_as_pontas_do_corredor() {
+        let map = corredor();
+        let roadmap = roadmap_voronoi(&map, 2.0);
+        let (inicio, fim) = (Point { x: 3.0, y: 11.0 }, Point { x: 76.0, y: 18.0 });
+        let (graph, caminho) = caminho_voronoi(&roadmap, &map, inicio, fim).unwrap();
+
+        assert_eq!(graph.vertices[caminho[0]], inicio);
+        assert_eq!(graph.vertices[*caminho.last().unwrap()], fim);
+        // O roadmap original não é alterado pela consulta
+        assert_eq!(graph.vertices.len(), roadmap.graph.vertices.len() + 2);
+        // O comprimento fica perto do comprimento do corredor
+        let comprimento: f64 = caminho.windows(2).map(|par| graph.adj[par[0]].iter().find(|e| e.to_idx == par[1]).unwrap().weight.valor()).sum();
+        assert!(comprimento >= inicio.dist(&fim) && comprimento < 1.2 * inicio.dist(&fim), "comprimento {}", comprimento);
+    }
+
+    #[test]
+    fn passagem_estreita_e_cortada_pela_folga_minima() {
+        // O mesmo corredor com um estreitamento de 4 pixels (y de 13 a 16) entre x = 38 e x = 41
+        let map = mapa_de_teste(80, 30, |x, y| x == 0 || x == 79 || y < 10 || y > 19 || ((38..=41).contains(&x) && !(13..=16).contains(&y)));
+        let (inicio, fim) = (Point { x: 5.0, y: 14.0 }, Point { x: 74.0, y: 14.0 });
+        assert!(caminho_voronoi(&roadmap_voronoi(&map, 1.0), &map, inicio, fim).is_ok());
+        assert!(caminho_voronoi(&roadmap_voronoi(&map, 3.0), &map, inicio, fim).is_err());
+    }
+
+    #[test]
+    fn conectar_ao_roadmap_recusa_ponto_em_obstaculo() {
+        let map = corredor();
+        let mut graph = roadmap_voronoi(&map, 2.0).graph;
+        let n = graph.vertices.len();
+        assert!(conectar_ao_roadmap(&mut graph, &map, Point { x: 40.0, y: 5.0 }).is_err());
+        assert_eq!(graph.vertices.len(), n);
+
+        let idx = conectar_ao_roadmap(&mut graph, &map, Point { x: 40.0, y: 11.0 }).unwrap();
+        assert_eq!(idx, n);
+        assert_eq!(graph.adj[idx].len(), 1);
+    }
+
+    /// Máscara a partir de um desenho, uma linha por string ('#' = pixel do esqueleto)
+    fn mascara(desenho: &[&str]) -> Mascara {
+        Mascara {
+            largura: desenho[0].len(),
+            altura: desenho.len(),
+            pixels: desenho.iter().flat_map(|linha| linha.chars().map(|c| c == '#')).collect(),
+        }
+    }
+
+    fn pixels_ligados(m: &Mascara) -> Vec<(i64, i64)> {
+        (0..m.altura as i64).flat_map(|y| (0..m.largura as i64).map(move |x| (x, y))).filter(|&(x, y)| m.get(x, y)).collect()
+    }
+
+    /// Quantidade de componentes de 8 vizinhos
+    fn componentes(m: &Mascara) -> usize {
+        let mut visitado = vec![false; m.largura * m.altura];
+        let mut total = 0;
+        for (x, y) in pixels_ligados(m) {
+            if visitado[y as usize * m.largura + x as usize] {
+                continue;
+            }
+            total += 1;
+            let mut pilha = vec![(x, y)];
+            visitado[y as usize * m.largura + x as usize] = true;
+            while let Some((cx, cy)) = pilha.pop() {
+                for &(dx, dy) in VIZINHOS.iter() {
+                    let (nx, ny) = (cx + dx, cy + dy);
+                    if m.get(nx, ny) && !visitado[ny as usize * m.largura + nx as usize] {
+                        visitado[ny as usize * m.largura + nx as usize] = true;
+                        pilha.push((nx, ny));
+                    }
+                }
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn escada_vira_diagonal_sem_perder_as_pontas() {
+        let mut m = mascara(&[
+            "........",
+            ".##.....",
+            "..##....",
+            "...##...",
+            "....##..",
+            "........",
+        ]);
+        limpar_escadas(&mut m);
+        assert_eq!(pixels_ligados(&m), vec![(1, 1), (2, 2), (3, 3), (4, 4), (5, 4)]);
+        assert_eq!(componentes(&m), 1);
+    }
+
+    #[test]
+    fn ramo_em_escada_nao_e_comido() {
+        // Ramo em escada saindo de uma linha horizontal, com a ponta em (9, 1)
+        let mut m = mascara(&[
+            "............",
+            ".........#..",
+            "........##..",
+            ".......##...",
+            "......##....",
+            ".##########.",
+            "............",
+        ]);
+        limpar_escadas(&mut m);
+        assert_eq!(componentes(&m), 1);
+        for ponta in [(1, 5), (10, 5), (9, 1)] {
+            assert!(m.get(ponta.0, ponta.1), "a ponta {:?} foi removida", ponta);
+        }
+        // Três pontas (grau 1) e nenhum pixel de grau 2 com vizinhos encostados
+        let pixels = pixels_ligados(&m);
+        assert_eq!(pixels.iter().filter(|&&(x, y)| m.grau(x, y) == 1).count(), 3);
+        assert!(pixels.iter().all(|&(x, y)| m.grau(x, y) != 2 || !eh_escada(&m, x, y)));
+    }
+
+    #[test]
+    fn bloco_2x2_continua_conexo() {
+        let mut m = mascara(&["....", ".##.", ".##.", "...."]);
+        limpar_escadas(&mut m);
+        assert!(!pixels_ligados(&m).is_empty());
+        assert_eq!(componentes(&m), 1);
+    }
+}